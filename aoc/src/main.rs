@@ -0,0 +1,75 @@
+//! Command line executable for running any day through its shared `Solution`
+use std::io::BufRead;
+
+use aoc_utils::{exit_with, open_or_exit, solve, ParseError, Part};
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Solve a single day
+    Run {
+        /// Day of the advent calendar
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=12))]
+        day: u8,
+
+        /// Part to solve; both parts are solved if omitted
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        /// Input file
+        #[arg(short)]
+        input_file: String,
+    },
+}
+
+fn main() {
+    let args = Args::parse();
+
+    match args.command {
+        Command::Run {
+            day,
+            part,
+            input_file,
+        } => {
+            let parts = match part {
+                Some(1) => vec![Part::Part1],
+                Some(_) => vec![Part::Part2],
+                None => vec![Part::Part1, Part::Part2],
+            };
+            for part in parts {
+                // Each part consumes its own parsed input, so re-open the file per part
+                let file = open_or_exit(&input_file);
+                match run_day(day, file, part) {
+                    Ok(answer) => println!("{}", answer),
+                    Err(e) => exit_with(e.with_file(&input_file)),
+                }
+            }
+        }
+    }
+}
+
+/// Dispatch to the `Solution` for the given day
+fn run_day<R: BufRead>(day: u8, reader: R, part: Part) -> Result<String, ParseError> {
+    match day {
+        1 => solve::<day_one::DayOne, _>(reader, part),
+        2 => solve::<day_two::DayTwo, _>(reader, part),
+        3 => solve::<day_three::DayThree, _>(reader, part),
+        4 => solve::<day_four::DayFour, _>(reader, part),
+        5 => solve::<day_five::DayFive, _>(reader, part),
+        6 => solve::<day_six::DaySix, _>(reader, part),
+        7 => solve::<day_seven::DaySeven, _>(reader, part),
+        8 => solve::<day_eight::DayEight, _>(reader, part),
+        9 => solve::<day_nine::DayNine, _>(reader, part),
+        10 => solve::<day_ten::DayTen, _>(reader, part),
+        11 => solve::<day_eleven::DayEleven, _>(reader, part),
+        12 => solve::<day_twelve::DayTwelve, _>(reader, part),
+        _ => unreachable!("Day is validated by the argument parser"),
+    }
+}
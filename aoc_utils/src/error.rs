@@ -0,0 +1,130 @@
+//! Parse errors that remember where in the input they happened.
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// An error in the puzzle input, located by line and column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Name of the input file, if known
+    pub file: Option<String>,
+    /// 1-based line number, or 0 if not yet known
+    pub line: usize,
+    /// 1-based column of the offending token, counted in characters
+    pub column: usize,
+    /// The offending token; empty if something was missing
+    pub token: String,
+    /// Full text of the offending line
+    pub line_text: String,
+    /// What went wrong
+    pub message: String,
+}
+impl ParseError {
+    /// Error at a 1-based character column of a line
+    pub fn at_column(
+        line_text: &str,
+        column: usize,
+        token: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            file: None,
+            line: 0,
+            column,
+            token: token.to_string(),
+            line_text: line_text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Error for `token`, which should be a slice borrowed from `line_text`
+    pub fn at_token(line_text: &str, token: &str, message: impl Into<String>) -> Self {
+        let offset = token_offset(line_text, token);
+        let column = line_text[..offset].chars().count() + 1;
+        Self::at_column(line_text, column, token, message)
+    }
+
+    /// Error for something expected but missing at the end of a line
+    pub fn missing(line_text: &str, what: &str) -> Self {
+        let column = line_text.chars().count() + 1;
+        Self::at_column(line_text, column, "", format!("expected {}", what))
+    }
+
+    /// Error for a problem with the input as a whole rather than any one line
+    pub fn input(message: impl Into<String>) -> Self {
+        Self::at_column("", 0, "", message)
+    }
+
+    /// Attach the 1-based line number, unless one is already set
+    pub fn with_line(mut self, line: usize) -> Self {
+        if self.line == 0 {
+            self.line = line;
+        }
+        self
+    }
+
+    /// Attach the input file name
+    pub fn with_file(mut self, file: &str) -> Self {
+        self.file = Some(file.to_string());
+        self
+    }
+}
+
+/// Byte offset of `token` within `line`, falling back to a search if it isn't a borrowed slice
+fn token_offset(line: &str, token: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let token_start = token.as_ptr() as usize;
+    if token_start >= start && token_start + token.len() <= start + line.len() {
+        token_start - start
+    } else {
+        line.find(token).unwrap_or(0)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "error: {}", self.message)?;
+        let file = self.file.as_deref().unwrap_or("<input>");
+        if self.line == 0 {
+            return write!(f, " --> {}", file);
+        }
+        let gutter = self.line.to_string().len();
+        writeln!(
+            f,
+            "{:gutter$}--> {}:{}:{}",
+            "", file, self.line, self.column
+        )?;
+        writeln!(f, "{:gutter$} |", "")?;
+        writeln!(f, "{} | {}", self.line, self.line_text)?;
+        let carets = self.token.chars().count().max(1);
+        write!(
+            f,
+            "{:gutter$} | {}{}",
+            "",
+            " ".repeat(self.column.saturating_sub(1)),
+            "^".repeat(carets)
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse a token borrowed from `line`, reporting its position on failure
+pub fn parse_token<T>(line: &str, token: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    token
+        .parse()
+        .map_err(|e| ParseError::at_token(line, token, format!("cannot parse `{}`: {}", token, e)))
+}
+
+/// Take the next token from `tokens`, reporting `what` was expected if there isn't one
+pub fn next_token<'a, I>(line: &str, tokens: &mut I, what: &str) -> Result<&'a str, ParseError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    tokens.next().ok_or_else(|| ParseError::missing(line, what))
+}
@@ -0,0 +1,358 @@
+//! A rectangular grid of cells addressed by `(row, col)`, for the character map puzzles.
+use std::{
+    fmt::{self, Display},
+    io::BufRead,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use crate::{try_line_chars_to_int_vec, try_read_lines, ParseError};
+
+/// One of the four cardinal directions; north is towards row 0 and west towards column 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+impl Direction {
+    /// Every direction, in a fixed order
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+    ];
+
+    /// Change in `(row, col)` from taking one step this way
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Self::North => (-1, 0),
+            Self::South => (1, 0),
+            Self::East => (0, 1),
+            Self::West => (0, -1),
+        }
+    }
+
+    /// The direction pointing the other way
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::East => Self::West,
+            Self::West => Self::East,
+        }
+    }
+}
+
+/// Offsets of the eight cells surrounding a cell
+const NEIGHBOURS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular grid stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    n_rows: usize,
+    n_cols: usize,
+}
+impl<T> Grid<T> {
+    /// Build a grid from rows, which must all be the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, ParseError> {
+        let n_rows = rows.len();
+        let n_cols = rows.first().map_or(0, |row| row.len());
+        let mut cells = Vec::with_capacity(n_rows * n_cols);
+        for (idx, row) in rows.into_iter().enumerate() {
+            if row.len() != n_cols {
+                return Err(ParseError::input(format!(
+                    "row has {} columns but the first row has {}",
+                    row.len(),
+                    n_cols
+                ))
+                .with_line(idx + 1));
+            }
+            cells.extend(row);
+        }
+        Ok(Self {
+            cells,
+            n_rows,
+            n_cols,
+        })
+    }
+
+    /// Parse a character map, converting each character with `f`
+    pub fn parse_chars<R, F>(reader: R, f: F) -> Result<Self, ParseError>
+    where
+        R: BufRead,
+        F: Fn(char) -> Option<T>,
+    {
+        let lines = try_read_lines(reader)?;
+        let rows = lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                line.char_indices()
+                    .map(|(offset, c)| {
+                        f(c).ok_or_else(|| {
+                            ParseError::at_token(
+                                line,
+                                &line[offset..offset + c.len_utf8()],
+                                format!("unexpected character `{}`", c),
+                            )
+                            .with_line(idx + 1)
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<Vec<T>>, ParseError>>()?;
+        Self::from_rows(rows).map_err(|e| locate_row(e, &lines))
+    }
+
+    /// Parse a map where every character is a single digit number
+    pub fn parse_digits<R: BufRead>(reader: R) -> Result<Self, ParseError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        let lines = try_read_lines(reader)?;
+        let rows = lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| try_line_chars_to_int_vec(line).map_err(|e| e.with_line(idx + 1)))
+            .collect::<Result<Vec<Vec<T>>, ParseError>>()?;
+        Self::from_rows(rows).map_err(|e| locate_row(e, &lines))
+    }
+
+    /// Grid of the given shape with every cell set to `value`
+    pub fn filled(n_rows: usize, n_cols: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![value; n_rows * n_cols],
+            n_rows,
+            n_cols,
+        }
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    /// Bounds-checked access to a cell
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        self.index_of(pos).map(|idx| &self.cells[idx])
+    }
+
+    /// Bounds-checked mutable access to a cell
+    pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        self.index_of(pos).map(|idx| &mut self.cells[idx])
+    }
+
+    /// Position one step from `pos` in `dir`, if that is still inside the grid
+    pub fn step(&self, pos: (usize, usize), dir: Direction) -> Option<(usize, usize)> {
+        self.offset(pos, dir.offset())
+    }
+
+    /// The up to four cells sharing an edge with `pos`
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir))
+    }
+
+    /// The up to eight cells sharing an edge or corner with `pos`
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS8
+            .into_iter()
+            .filter_map(move |offset| self.offset(pos, offset))
+    }
+
+    /// The cells of a row, west to east
+    pub fn row(&self, row: usize) -> impl Iterator<Item = &T> {
+        self.cells[row * self.n_cols..(row + 1) * self.n_cols].iter()
+    }
+
+    /// The cells of a column, north to south
+    ///
+    /// Panics if `col` is out of bounds, as `row` does.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(
+            col < self.n_cols,
+            "column {} is out of bounds for a grid with {} columns",
+            col,
+            self.n_cols
+        );
+        self.cells.iter().skip(col).step_by(self.n_cols)
+    }
+
+    /// Cells from `pos` (exclusive) out to the edge of the grid in `dir`
+    pub fn ray(&self, pos: (usize, usize), dir: Direction) -> Ray<'_, T> {
+        Ray {
+            grid: self,
+            pos,
+            dir,
+        }
+    }
+
+    /// Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let n_cols = self.n_cols;
+        (0..self.n_rows).flat_map(move |row| (0..n_cols).map(move |col| (row, col)))
+    }
+
+    /// Every position alongside its cell, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Position of the first cell, row by row, matching `predicate`
+    pub fn position<P: Fn(&T) -> bool>(&self, predicate: P) -> Option<(usize, usize)> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|idx| (idx / self.n_cols, idx % self.n_cols))
+    }
+
+    /// Position of the first cell equal to `value`, such as a start marker
+    pub fn find(&self, value: &T) -> Option<(usize, usize)>
+    where
+        T: PartialEq,
+    {
+        self.position(|cell| cell == value)
+    }
+
+    /// New grid of the same shape with `f` applied to every cell
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            n_rows: self.n_rows,
+            n_cols: self.n_cols,
+        }
+    }
+
+    fn index_of(&self, (row, col): (usize, usize)) -> Option<usize> {
+        if row < self.n_rows && col < self.n_cols {
+            Some(row * self.n_cols + col)
+        } else {
+            None
+        }
+    }
+
+    fn offset(
+        &self,
+        (row, col): (usize, usize),
+        (d_row, d_col): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let row = row.checked_add_signed(d_row)?;
+        let col = col.checked_add_signed(d_col)?;
+        self.index_of((row, col)).map(|_| (row, col))
+    }
+}
+
+/// Attach the offending line's text to a ragged row error from `from_rows`
+fn locate_row(err: ParseError, lines: &[String]) -> ParseError {
+    match lines.get(err.line.wrapping_sub(1)) {
+        Some(line) => ParseError {
+            line_text: line.clone(),
+            column: line.chars().count() + 1,
+            ..err
+        },
+        None => err,
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", pos))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", pos))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.n_rows {
+            for cell in self.row(row) {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the cells in a straight line from a starting position
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    pos: (usize, usize),
+    dir: Direction,
+}
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = ((usize, usize), &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pos = self.grid.step(self.pos, self.dir)?;
+        Some((self.pos, &self.grid[self.pos]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<u32> {
+        Grid::parse_digits("123\n456\n789".as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_rays_and_neighbours() {
+        let grid = grid();
+        let east: Vec<u32> = grid.ray((1, 0), Direction::East).map(|(_, v)| *v).collect();
+        assert_eq!(east, vec![5, 6]);
+        let north: Vec<u32> = grid
+            .ray((2, 2), Direction::North)
+            .map(|(_, v)| *v)
+            .collect();
+        assert_eq!(north, vec![6, 3]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5, 8]);
+        assert_eq!(grid.neighbours4((0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.find(&6), Some((1, 2)));
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456\n789\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 is out of bounds")]
+    fn test_column_out_of_bounds() {
+        grid().column(3).count();
+    }
+
+    #[test]
+    fn test_ragged_rows() {
+        let err = Grid::<u32>::parse_digits("123\n45\n".as_bytes()).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+}
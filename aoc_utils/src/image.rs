@@ -0,0 +1,103 @@
+//! Writing grids out as Netpbm images, which most image viewers can open, or as CSV.
+use std::{
+    fmt::Display,
+    io::{self, Write},
+};
+
+use crate::Grid;
+
+/// A colour as red, green and blue intensities
+pub type Rgb = [u8; 3];
+
+/// Blend from `from` to `to`, with `t` running from 0 to 1
+pub fn lerp(from: Rgb, to: Rgb, t: f64) -> Rgb {
+    let t = t.clamp(0.0, 1.0);
+    let mut colour = [0; 3];
+    for (channel, (a, b)) in colour.iter_mut().zip(from.iter().zip(to.iter())) {
+        *channel = (*a as f64 + (*b as f64 - *a as f64) * t).round() as u8;
+    }
+    colour
+}
+
+/// Write `image` as a binary PPM, drawing each cell as a `scale` by `scale` square
+pub fn write_ppm<W: Write>(mut writer: W, image: &Grid<Rgb>, scale: usize) -> io::Result<()> {
+    let scale = scale.max(1);
+    write!(
+        writer,
+        "P6\n{} {}\n255\n",
+        image.n_cols() * scale,
+        image.n_rows() * scale
+    )?;
+    for row in 0..image.n_rows() {
+        let line: Vec<u8> = image
+            .row(row)
+            .flat_map(|colour| std::iter::repeat_n(colour, scale).flatten())
+            .copied()
+            .collect();
+        for _ in 0..scale {
+            writer.write_all(&line)?;
+        }
+    }
+    writer.flush()
+}
+
+/// Write `image` as a binary PGM of grey levels, drawing each cell as a `scale` by `scale`
+/// square
+pub fn write_pgm<W: Write>(mut writer: W, image: &Grid<u8>, scale: usize) -> io::Result<()> {
+    let scale = scale.max(1);
+    write!(
+        writer,
+        "P5\n{} {}\n255\n",
+        image.n_cols() * scale,
+        image.n_rows() * scale
+    )?;
+    for row in 0..image.n_rows() {
+        let line: Vec<u8> = image
+            .row(row)
+            .flat_map(|level| std::iter::repeat_n(*level, scale))
+            .collect();
+        for _ in 0..scale {
+            writer.write_all(&line)?;
+        }
+    }
+    writer.flush()
+}
+
+/// Write a grid as comma separated values, one row per line
+pub fn write_csv<W: Write, T: Display>(mut writer: W, grid: &Grid<T>) -> io::Result<()> {
+    for row in 0..grid.n_rows() {
+        let cells: Vec<String> = grid.row(row).map(|cell| cell.to_string()).collect();
+        writeln!(writer, "{}", cells.join(","))?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ppm() {
+        let image = Grid::from_rows(vec![vec![[255, 0, 0], [0, 0, 255]]]).unwrap();
+        let mut out = Vec::new();
+        write_ppm(&mut out, &image, 2).unwrap();
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let pixels = &out[header.len()..];
+        assert_eq!(pixels.len(), 4 * 2 * 3);
+        assert_eq!(&pixels[..6], &[255, 0, 0, 255, 0, 0]);
+        assert_eq!(&pixels[12..], &pixels[..12]);
+        assert_eq!(lerp([0, 0, 0], [200, 100, 0], 0.5), [100, 50, 0]);
+    }
+
+    #[test]
+    fn test_pgm_and_csv() {
+        let grid = Grid::from_rows(vec![vec![0, 255], vec![7, 8]]).unwrap();
+        let mut out = Vec::new();
+        write_pgm(&mut out, &grid, 1).unwrap();
+        assert_eq!(out, b"P5\n2 2\n255\n\x00\xff\x07\x08");
+        let mut out = Vec::new();
+        write_csv(&mut out, &grid).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "0,255\n7,8\n");
+    }
+}
@@ -1,48 +1,116 @@
 //! Useful utils to read in AoC files and convert them into data structures to manipulate.
 
 use std::{
-    fmt::Debug,
+    fmt::Display,
     fs::File,
     io::{BufRead, BufReader},
     str::FromStr,
 };
 
-/// Read in a file and represent it as a vector of Strings
-pub fn file_to_string_vec(filename: &str) -> Vec<String> {
-    BufReader::new(File::open(filename).unwrap())
+mod error;
+mod grid;
+mod image;
+mod solution;
+pub use error::{next_token, parse_token, ParseError};
+pub use grid::{Direction, Grid, Ray};
+pub use image::{lerp, write_csv, write_pgm, write_ppm, Rgb};
+pub use solution::{exit_with, open_or_exit, parse_or_exit, run, run_part, solve, Part, Solution};
+
+/// Read every line of a reader, reporting which line could not be read
+pub fn try_read_lines<R: BufRead>(reader: R) -> Result<Vec<String>, ParseError> {
+    reader
         .lines()
-        .map(|x| x.unwrap())
+        .enumerate()
+        .map(|(idx, line)| {
+            line.map_err(|e| {
+                ParseError::input(format!("cannot read line: {}", e)).with_line(idx + 1)
+            })
+        })
+        .collect()
+}
+
+/// Parse every line of a reader with `f`, tagging any error with its line number
+pub fn try_parse_lines<R, F, T>(reader: R, f: F) -> Result<Vec<T>, ParseError>
+where
+    R: BufRead,
+    F: Fn(&str) -> Result<T, ParseError>,
+{
+    try_read_lines(reader)?
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| f(line).map_err(|e| e.with_line(idx + 1)))
+        .collect()
+}
+
+/// Parse every character of a line as a number
+pub fn try_line_chars_to_int_vec<T>(line: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    line.char_indices()
+        .map(|(offset, c)| parse_token(line, &line[offset..offset + c.len_utf8()]))
         .collect()
 }
 
+/// Read in a file and represent it as a vector of Strings
+pub fn try_file_to_string_vec(filename: &str) -> Result<Vec<String>, ParseError> {
+    let file = File::open(filename)
+        .map_err(|e| ParseError::input(format!("cannot open file: {}", e)).with_file(filename))?;
+    try_read_lines(BufReader::new(file)).map_err(|e| e.with_file(filename))
+}
+
+/// Read in a file and represent it as a vector of Strings
+pub fn file_to_string_vec(filename: &str) -> Vec<String> {
+    try_file_to_string_vec(filename).unwrap_or_else(|e| panic!("{}", e))
+}
+
 /// Read in a file and parse as a vector of numbers
-pub fn file_to_num_vec<T>(filename: &str) -> Vec<T>
+pub fn try_file_to_num_vec<T>(filename: &str) -> Result<Vec<T>, ParseError>
 where
     T: FromStr,
-    <T as FromStr>::Err: Debug,
+    <T as FromStr>::Err: Display,
 {
-    file_to_string_vec(filename)
+    try_file_to_string_vec(filename)?
         .iter()
-        .map(|x| x.parse::<T>().unwrap())
+        .enumerate()
+        .map(|(idx, x)| parse_token(x, x).map_err(|e| e.with_line(idx + 1).with_file(filename)))
         .collect()
 }
 
+/// Read in a file and parse as a vector of numbers
+pub fn file_to_num_vec<T>(filename: &str) -> Vec<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    try_file_to_num_vec(filename).unwrap_or_else(|e| panic!("{}", e))
+}
+
 /// Read in a file and represent as a vector of characters
-pub fn file_chars_to_int_vec<T>(filename: &str) -> Vec<Vec<T>>
+pub fn try_file_chars_to_int_vec<T>(filename: &str) -> Result<Vec<Vec<T>>, ParseError>
 where
     T: FromStr,
-    <T as FromStr>::Err: Debug,
+    <T as FromStr>::Err: Display,
 {
-    file_to_string_vec(filename)
+    try_file_to_string_vec(filename)?
         .iter()
-        .map(|x| {
-            x.chars()
-                .map(|c| c.to_string().parse::<T>().unwrap())
-                .collect()
+        .enumerate()
+        .map(|(idx, x)| {
+            try_line_chars_to_int_vec(x).map_err(|e| e.with_line(idx + 1).with_file(filename))
         })
         .collect()
 }
 
+/// Read in a file and represent as a vector of characters
+pub fn file_chars_to_int_vec<T>(filename: &str) -> Vec<Vec<T>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    try_file_chars_to_int_vec(filename).unwrap_or_else(|e| panic!("{}", e))
+}
+
 /// Split a string into a vector of words
 pub fn split_by_whitespace(input: &str) -> Vec<&str> {
     input.split(' ').collect()
@@ -54,28 +122,64 @@ pub fn str_array_to_vec(input: &[&str]) -> Vec<String> {
     input.iter().map(|x| x.to_string()).collect()
 }
 
+/// Convert an array of strings to an array of numbers, treating each string as a line
+pub fn try_str_array_to_int_vec(input: &[&str]) -> Result<Vec<i32>, ParseError> {
+    input
+        .iter()
+        .enumerate()
+        .map(|(idx, x)| parse_token(x, x).map_err(|e| e.with_line(idx + 1)))
+        .collect()
+}
+
 /// Convert an array of strings to an array of numbers
 pub fn str_array_to_int_vec(input: &[&str]) -> Vec<i32> {
-    input.iter().map(|x| x.parse::<i32>().unwrap()).collect()
+    try_str_array_to_int_vec(input).unwrap_or_else(|e| panic!("{}", e))
 }
 
 /// Convert lines to a vector of strings
 pub fn str_to_string_vec(input: &str) -> Vec<String> {
-    input.lines().into_iter().map(|x| x.to_string()).collect()
+    input.lines().map(|x| x.to_string()).collect()
+}
+
+/// Convert a file to vector of vector of characters; see `Grid::parse_digits` for a grid instead
+pub fn try_str_chars_to_int_vec<T>(input: &str) -> Result<Vec<Vec<T>>, ParseError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    try_parse_lines(input.as_bytes(), try_line_chars_to_int_vec)
 }
 
 /// Convert a file to vector of vector of characters
 pub fn str_chars_to_int_vec<T>(input: &str) -> Vec<Vec<T>>
 where
     T: FromStr,
-    <T as FromStr>::Err: Debug,
+    <T as FromStr>::Err: Display,
 {
-    str_to_string_vec(input)
-        .iter()
-        .map(|x| {
-            x.chars()
-                .map(|c| c.to_string().parse::<T>().unwrap())
-                .collect()
-        })
-        .collect()
+    try_str_chars_to_int_vec(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_location() {
+        let err = try_str_chars_to_int_vec::<u32>("123\n45x6\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (2, 3, "x"));
+        assert_eq!(
+            err.to_string(),
+            "error: cannot parse `x`: invalid digit found in string
+ --> <input>:2:3
+  |
+2 | 45x6
+  |   ^"
+        );
+    }
+
+    #[test]
+    fn test_trailing_space() {
+        let err = try_str_array_to_int_vec(&["1", "2 "]).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
 }
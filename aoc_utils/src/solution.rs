@@ -0,0 +1,96 @@
+//! Shared interface every day implements, plus the command line glue to run it.
+use std::{
+    fmt::Debug,
+    fs::File,
+    io::{BufRead, BufReader},
+    process,
+};
+
+use clap::Parser;
+
+use crate::ParseError;
+
+/// A single day's puzzle, split into parsing and the two parts
+pub trait Solution {
+    /// Parsed representation of the puzzle input
+    type Input;
+    /// Answer to part one
+    type PartOne: Debug;
+    /// Answer to part two
+    type PartTwo: Debug;
+
+    /// Parse the puzzle input
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError>;
+
+    /// Solve part one, failing if the input turns out to have no answer
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError>;
+
+    /// Solve part two, failing if the input turns out to have no answer
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError>;
+}
+
+// Which half of the puzzle to solve. Not a doc comment, as clap would use it as the `about` text
+// of any executable that flattens this into its own subcommands.
+#[derive(clap::Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    /// Solve part one
+    Part1,
+    /// Solve part two
+    Part2,
+}
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Input file
+    #[arg(short)]
+    input_file: String,
+
+    #[command(subcommand)]
+    part: Part,
+}
+
+/// Parse the input and solve the requested part, formatting the answer for display
+pub fn solve<S: Solution, R: BufRead>(reader: R, part: Part) -> Result<String, ParseError> {
+    let input = S::parse(reader)?;
+    Ok(match part {
+        Part::Part1 => format!("{:?}", S::part_one(input)?),
+        Part::Part2 => format!("{:?}", S::part_two(input)?),
+    })
+}
+
+/// Open an input file, printing a diagnostic and exiting if it cannot be read
+pub fn open_or_exit(filename: &str) -> BufReader<File> {
+    match File::open(filename) {
+        Ok(file) => BufReader::new(file),
+        Err(e) => {
+            exit_with(ParseError::input(format!("cannot open file: {}", e)).with_file(filename))
+        }
+    }
+}
+
+/// Print a parse diagnostic and exit non-zero
+pub fn exit_with(err: ParseError) -> ! {
+    eprintln!("{}", err);
+    process::exit(1)
+}
+
+/// Parse an input file, printing a diagnostic and exiting if it is malformed
+pub fn parse_or_exit<S: Solution>(filename: &str) -> S::Input {
+    S::parse(open_or_exit(filename)).unwrap_or_else(|e| exit_with(e.with_file(filename)))
+}
+
+/// Solve one part of an input file and print the answer, for executables with their own arguments
+pub fn run_part<S: Solution>(filename: &str, part: Part) {
+    match solve::<S, _>(open_or_exit(filename), part) {
+        Ok(answer) => println!("{}", answer),
+        Err(e) => exit_with(e.with_file(filename)),
+    }
+}
+
+/// Entry point shared by the per-day executables
+pub fn run<S: Solution>() {
+    let args = Args::parse();
+
+    run_part::<S>(&args.input_file, args.part);
+}
@@ -0,0 +1,242 @@
+//! Solutions for part one and part two
+use std::io::BufRead;
+
+use aoc_utils::{lerp, Direction, Grid, ParseError, Rgb, Solution};
+
+type ReturnType = i64;
+type VectorType = Grid<i64>;
+
+/// Solution for day eight
+pub struct DayEight;
+impl Solution for DayEight {
+    type Input = VectorType;
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        Grid::parse_digits(reader)
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        Ok(part_one_internal(input))
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        Ok(part_two_internal(input))
+    }
+}
+
+/// Cells along the edge of the grid on the `dir` side
+fn edge<T>(grid: &Grid<T>, dir: Direction) -> Vec<(usize, usize)> {
+    let (n_rows, n_cols) = (grid.n_rows(), grid.n_cols());
+    match dir {
+        Direction::North => (0..n_cols).map(|col| (0, col)).collect(),
+        Direction::South => (0..n_cols).map(|col| (n_rows - 1, col)).collect(),
+        Direction::West => (0..n_rows).map(|row| (row, 0)).collect(),
+        Direction::East => (0..n_rows).map(|row| (row, n_cols - 1)).collect(),
+    }
+}
+
+/// Look along `dir` from every cell, returning whether the edge can be seen and how many trees
+/// can be seen before one at least as tall
+///
+/// Each row or column is swept once from the `dir` edge, keeping a stack of the trees not yet
+/// hidden behind a taller one, so the whole grid takes O(n·m).
+pub fn sweep<T: Ord>(grid: &Grid<T>, dir: Direction) -> (Grid<bool>, Grid<usize>) {
+    let mut visible = grid.map(|_| false);
+    let mut distances = grid.map(|_| 0);
+    if grid.n_rows() == 0 || grid.n_cols() == 0 {
+        return (visible, distances);
+    }
+    let mut stack: Vec<(&T, usize)> = Vec::new();
+    for start in edge(grid, dir) {
+        stack.clear();
+        let cells = std::iter::once((start, &grid[start])).chain(grid.ray(start, dir.opposite()));
+        for (idx, (pos, height)) in cells.enumerate() {
+            // Shorter trees are hidden from here on by this one
+            while stack.last().is_some_and(|(other, _)| *other < height) {
+                stack.pop();
+            }
+            match stack.last() {
+                Some((_, blocker)) => distances[pos] = idx - blocker,
+                None => {
+                    visible[pos] = true;
+                    distances[pos] = idx;
+                }
+            }
+            stack.push((height, idx));
+        }
+    }
+    (visible, distances)
+}
+
+/// Whether each tree can be seen from outside the grid
+pub fn visibility<T: Ord>(grid: &Grid<T>) -> Grid<bool> {
+    let mut visible = grid.map(|_| false);
+    for dir in Direction::ALL {
+        let (from_dir, _) = sweep(grid, dir);
+        for (pos, seen) in from_dir.iter() {
+            visible[pos] |= *seen;
+        }
+    }
+    visible
+}
+
+/// Product of the viewing distances in every direction from each tree
+pub fn scenic_scores<T: Ord>(grid: &Grid<T>) -> Grid<usize> {
+    let mut scores = grid.map(|_| 1);
+    for dir in Direction::ALL {
+        let (_, distances) = sweep(grid, dir);
+        for (pos, distance) in distances.iter() {
+            scores[pos] *= distance;
+        }
+    }
+    scores
+}
+
+fn part_one_internal(input: VectorType) -> ReturnType {
+    visibility(&input).iter().filter(|(_, seen)| **seen).count() as ReturnType
+}
+
+fn part_two_internal(input: VectorType) -> ReturnType {
+    scenic_scores(&input)
+        .iter()
+        .map(|(_, score)| *score as ReturnType)
+        .max()
+        .unwrap_or(-1)
+}
+
+/// Position and score of the tree with the highest scenic score, preferring the first row by
+/// row on a tie
+pub fn best_tree(scores: &Grid<usize>) -> Option<((usize, usize), usize)> {
+    scores.iter().fold(None, |best, (pos, score)| match best {
+        Some((_, best_score)) if best_score >= *score => best,
+        _ => Some((pos, *score)),
+    })
+}
+
+/// Visible trees in white and hidden ones in black, for `write_pgm`
+pub fn visibility_image(visible: &Grid<bool>) -> Grid<u8> {
+    visible.map(|seen| if *seen { 255 } else { 0 })
+}
+
+/// Scenic scores from dark blue to yellow, on a square root scale so the few high scores don't
+/// wash out everything else, with the best tree in red
+pub fn scenic_image(scores: &Grid<usize>) -> Grid<Rgb> {
+    let best = best_tree(scores);
+    let max = best.map_or(0, |(_, score)| score).max(1) as f64;
+    let mut image = scores.map(|score| {
+        let t = (*score as f64 / max).sqrt();
+        if t < 0.5 {
+            lerp([10, 20, 80], [200, 50, 120], t * 2.0)
+        } else {
+            lerp([200, 50, 120], [255, 230, 60], t * 2.0 - 1.0)
+        }
+    });
+    if let Some((pos, _)) = best {
+        image[pos] = [255, 0, 0];
+    }
+    image
+}
+
+/// The forest for a terminal, with hidden trees dimmed and the best tree in red
+pub fn render_forest(input: &VectorType) -> String {
+    let visible = visibility(input);
+    let best = best_tree(&scenic_scores(input)).map(|(pos, _)| pos);
+    let mut out = String::new();
+    for row in 0..input.n_rows() {
+        for (col, height) in input.row(row).enumerate() {
+            let pos = (row, col);
+            let style = if Some(pos) == best {
+                "\x1b[1;97;41m"
+            } else if visible[pos] {
+                "\x1b[1;32m"
+            } else {
+                "\x1b[2m"
+            };
+            out.push_str(&format!("{}{}\x1b[0m", style, height));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> VectorType {
+        Grid::parse_digits(
+            "30373
+25512
+65332
+33549
+35390"
+                .as_bytes(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_one() {
+        assert_eq!(part_one_internal(input()), 21);
+    }
+
+    #[test]
+    fn test_two() {
+        assert_eq!(part_two_internal(input()), 8);
+    }
+
+    /// Look along every ray from every tree, the slow way
+    fn brute_force(grid: &VectorType) -> (Grid<bool>, Grid<usize>) {
+        let visible = grid.map(|_| false);
+        let scores = grid.map(|_| 1);
+        grid.iter().fold(
+            (visible, scores),
+            |(mut visible, mut scores), (pos, val)| {
+                for dir in Direction::ALL {
+                    let mut ray = grid.ray(pos, dir);
+                    let mut distance = 0;
+                    let blocked = ray.any(|(_, other)| {
+                        distance += 1;
+                        other >= val
+                    });
+                    visible[pos] |= !blocked;
+                    scores[pos] *= distance;
+                }
+                (visible, scores)
+            },
+        )
+    }
+
+    #[test]
+    fn test_best_tree() {
+        let scores = scenic_scores(&input());
+        assert_eq!(best_tree(&scores), Some(((3, 2), 8)));
+        let image = scenic_image(&scores);
+        assert_eq!(image[(3, 2)], [255, 0, 0]);
+        assert_eq!(image[(0, 0)], [10, 20, 80]);
+        let mask = visibility_image(&visibility(&input()));
+        assert_eq!((mask[(0, 0)], mask[(2, 2)]), (255, 0));
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        // A simple generator keeps the forest the same on every run
+        let mut seed: u64 = 8;
+        let rows = (0..23)
+            .map(|_| {
+                (0..17)
+                    .map(|_| {
+                        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                        (seed >> 60) as i64 % 10
+                    })
+                    .collect()
+            })
+            .collect();
+        let grid = Grid::from_rows(rows).unwrap();
+        let (visible, scores) = brute_force(&grid);
+        assert_eq!(visibility(&grid), visible);
+        assert_eq!(scenic_scores(&grid), scores);
+    }
+}
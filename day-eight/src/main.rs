@@ -1,12 +1,15 @@
 //! Command line executable for running part one and part two
 use std::{
     fs::File,
-    io::{BufRead, BufReader},
+    io::{self, BufWriter},
+    path::Path,
 };
 
+use aoc_utils::{parse_or_exit, run_part, write_csv, write_pgm, write_ppm, Part};
 use clap::Parser;
-
-use ndarray::{s, Array};
+use day_eight::{
+    best_tree, render_forest, scenic_image, scenic_scores, visibility, visibility_image, DayEight,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -16,191 +19,69 @@ struct Args {
     input_file: String,
 
     #[command(subcommand)]
-    part: Part,
+    command: Command,
 }
 
 #[derive(clap::Subcommand, Debug)]
-enum Part {
-    Part1,
-    Part2,
+enum Command {
+    #[command(flatten)]
+    Part(Part),
+    /// Draw the forest with visible trees in green and the best viewpoint in red
+    Render,
+    /// Write the visibility mask and scenic scores as images and CSV
+    Export {
+        /// Directory to write visibility.pgm, visibility.csv, scenic.ppm and scenic.csv to
+        #[arg(short, long, default_value = ".")]
+        output: String,
+
+        /// Width and height in pixels of each tree in the images
+        #[arg(long, default_value_t = 1)]
+        scale: usize,
+    },
 }
 
-fn main() {
-    let args = Args::parse();
-
-    let file = BufReader::new(File::open(args.input_file).expect("Cannot find file"));
-
-    let answer = match args.part {
-        Part::Part1 => part_one(file),
-        Part::Part2 => part_two(file),
-    };
-
-    println!("{:?}", answer);
-}
-
-fn part_one(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file, map_one);
-    part_one_internal(input)
-}
-
-fn part_two(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file, map_two);
-    part_two_internal(input)
-}
-
-fn parse_input<F, T>(file: BufReader<File>, f: F) -> Vec<T>
+/// Write one export file, exiting with a message if it can't be written
+fn export<F>(dir: &Path, name: &str, f: F)
 where
-    F: Fn(&str) -> T,
+    F: FnOnce(BufWriter<File>) -> io::Result<()>,
 {
-    file.lines().map(|x| f(x.unwrap().as_str())).collect()
-}
-
-// TODO -- Update this with the return type
-type ReturnType = i64;
-type VectorType = Vec<i64>;
-
-/// Map a line to a VectorType
-fn map_one(input: &str) -> VectorType {
-    input
-        .chars()
-        .map(|c| c.to_digit(10).unwrap() as i64)
-        .collect()
-}
-
-/// Map a line to a VectorType
-fn map_two(input: &str) -> VectorType {
-    input
-        .chars()
-        .map(|c| c.to_digit(10).unwrap() as i64)
-        .collect()
+    let path = dir.join(name);
+    if let Err(e) = File::create(&path).and_then(|file| f(BufWriter::new(file))) {
+        eprintln!("error: cannot write {}: {}", path.display(), e);
+        std::process::exit(1);
+    }
 }
 
-// TODO Implement this
-fn part_one_internal(input: Vec<VectorType>) -> ReturnType {
-    let n_rows = input.len();
-    let n_cols = input[0].len();
-    let arr = Array::from_iter(input.into_iter().flatten())
-        .into_shape((n_rows, n_cols))
-        .unwrap();
-    let mut n_visible = 0;
-    for row_idx in 0..n_rows {
-        for col_idx in 0..n_cols {
-            let val = *arr.get((row_idx, col_idx)).unwrap();
-
-            // Check the north slice
-            let north = arr
-                .slice(s![0..row_idx, col_idx])
-                .iter()
-                .max()
-                .copied()
-                .unwrap_or(-1);
-            let south = arr
-                .slice(s![row_idx + 1.., col_idx])
-                .iter()
-                .max()
-                .copied()
-                .unwrap_or(-1);
-            let east = arr
-                .slice(s![row_idx, 0..col_idx])
-                .iter()
-                .max()
-                .copied()
-                .unwrap_or(-1);
-            let west = arr
-                .slice(s![row_idx, col_idx + 1..])
-                .iter()
-                .max()
-                .copied()
-                .unwrap_or(-1);
+fn main() {
+    let args = Args::parse();
 
-            if north < val || south < val || east < val || west < val {
-                n_visible += 1;
+    match args.command {
+        Command::Part(part) => run_part::<DayEight>(&args.input_file, part),
+        Command::Render => {
+            let input = parse_or_exit::<DayEight>(&args.input_file);
+            print!("{}", render_forest(&input));
+            if let Some(((row, col), score)) = best_tree(&scenic_scores(&input)) {
+                println!(
+                    "Best tree at row {}, column {} with a scenic score of {}",
+                    row, col, score
+                );
             }
         }
-    }
-    n_visible
-}
-
-// TODO Implement this
-fn part_two_internal(input: Vec<VectorType>) -> ReturnType {
-    let n_rows = input.len();
-    let n_cols = input[0].len();
-    let arr = Array::from_iter(input.into_iter().flatten())
-        .into_shape((n_rows, n_cols))
-        .unwrap();
-    let mut highest_score = -1;
-    for row_idx in 0..n_rows {
-        for col_idx in 0..n_cols {
-            let val = *arr.get((row_idx, col_idx)).unwrap();
-
-            // Check the north slice
-            let north_view = arr
-                .slice(s![0..row_idx, col_idx])
-                .iter()
-                .rev()
-                .enumerate()
-                .find_map(|(idx, v)| {
-                    if v >= &val {
-                        Some(idx as ReturnType)
-                    } else {
-                        None
-                    }
-                })
-                .unwrap_or(row_idx as i64 - 1)
-                + 1;
-            let south_view = arr
-                .slice(s![row_idx + 1.., col_idx])
-                .iter()
-                .enumerate()
-                .find_map(|(idx, v)| {
-                    if v >= &val {
-                        Some(idx as ReturnType)
-                    } else {
-                        None
-                    }
-                })
-                .unwrap_or(n_rows as i64 - row_idx as i64 - 2)
-                + 1;
-            let east_view = arr
-                .slice(s![row_idx, 0..col_idx])
-                .iter()
-                .rev()
-                .enumerate()
-                .find_map(|(idx, v)| {
-                    if v >= &val {
-                        Some(idx as ReturnType)
-                    } else {
-                        None
-                    }
-                })
-                .unwrap_or(col_idx as i64 - 1)
-                + 1;
-            let west_view = arr
-                .slice(s![row_idx, col_idx + 1..])
-                .iter()
-                .enumerate()
-                .find_map(|(idx, v)| {
-                    if v >= &val {
-                        Some(idx as ReturnType)
-                    } else {
-                        None
-                    }
-                })
-                .unwrap_or(n_cols as i64 - col_idx as i64 - 2)
-                + 1;
-
-            let score = north_view * south_view * east_view * west_view;
-            highest_score = score.max(highest_score);
+        Command::Export { output, scale } => {
+            let input = parse_or_exit::<DayEight>(&args.input_file);
+            let dir = Path::new(&output);
+            let visible = visibility(&input);
+            let scores = scenic_scores(&input);
+            export(dir, "visibility.pgm", |w| {
+                write_pgm(w, &visibility_image(&visible), scale)
+            });
+            export(dir, "visibility.csv", |w| {
+                write_csv(w, &visible.map(|seen| u8::from(*seen)))
+            });
+            export(dir, "scenic.ppm", |w| {
+                write_ppm(w, &scenic_image(&scores), scale)
+            });
+            export(dir, "scenic.csv", |w| write_csv(w, &scores));
         }
     }
-    highest_score
-}
-
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn test_one() {}
-
-    #[test]
-    fn test_two() {}
 }
@@ -0,0 +1,189 @@
+//! Skipping ahead any number of rounds by following each item around until it repeats.
+//!
+//! Once worry levels are only kept modulo the monkeys' divisors, nothing an item does depends on
+//! any other item. Where an item is at the start of a round, and its worry level, decide
+//! everything it does from then on, and there are only so many of those states, so every item
+//! eventually goes round a cycle.
+use std::fmt::{self, Display};
+
+use crate::{Monkey, NotModular};
+
+/// Why rounds can't be skipped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnalysisError {
+    NotModular(NotModular),
+    /// The least common multiple of the divisors doesn't fit in a `usize`
+    ModulusTooLarge,
+}
+impl Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotModular(e) => write!(f, "{}", e),
+            Self::ModulusTooLarge => write!(f, "the monkeys' divisors multiply to too much"),
+        }
+    }
+}
+
+/// Where an item is at the start of a round
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ItemState {
+    pub monkey: usize,
+    /// Worry level modulo the least common multiple of the divisors
+    pub residue: usize,
+}
+
+/// The rounds an item takes to reach its cycle and to go round it once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemCycle {
+    pub start: ItemState,
+    pub lead_in: usize,
+    pub length: usize,
+}
+
+/// Follows items through the monkeys one at a time
+pub struct Analyser<'a> {
+    monkeys: &'a [Monkey],
+    modulus: usize,
+}
+impl<'a> Analyser<'a> {
+    pub fn new(monkeys: &'a [Monkey]) -> Result<Self, AnalysisError> {
+        for (idx, monkey) in monkeys.iter().enumerate() {
+            if !monkey.operation.is_modular() {
+                return Err(AnalysisError::NotModular(NotModular { monkey: idx }));
+            }
+        }
+        let modulus = monkeys
+            .iter()
+            .try_fold(1, |lcm: usize, monkey| {
+                lcm.checked_mul(monkey.diviser / gcd(lcm, monkey.diviser))
+            })
+            .ok_or(AnalysisError::ModulusTooLarge)?;
+        Ok(Self { monkeys, modulus })
+    }
+
+    /// Every item as it starts out
+    pub fn items(&self) -> Vec<ItemState> {
+        self.monkeys
+            .iter()
+            .enumerate()
+            .flat_map(|(monkey, m)| {
+                m.items.iter().map(move |item| ItemState {
+                    monkey,
+                    residue: item % self.modulus,
+                })
+            })
+            .collect()
+    }
+
+    /// Carry an item through one round, calling `inspect` with each monkey that inspects it
+    ///
+    /// Monkeys take their turns in order, so an item thrown to a later monkey is inspected again
+    /// in the same round and one thrown to an earlier monkey waits for the next.
+    pub fn round<F: FnMut(usize)>(&self, mut state: ItemState, mut inspect: F) -> ItemState {
+        loop {
+            let from = state.monkey;
+            let monkey = &self.monkeys[from];
+            inspect(from);
+            let residue = monkey
+                .operation
+                .eval_residue(state.residue, self.modulus)
+                .expect("operations are checked before analysing");
+            let (if_true, if_false) = monkey.monkey_throw_idxs;
+            let monkey = if residue.is_multiple_of(monkey.diviser) {
+                if_true
+            } else {
+                if_false
+            };
+            state = ItemState { monkey, residue };
+            if monkey < from {
+                return state;
+            }
+        }
+    }
+
+    /// Find the cycle an item falls into, with Brent's algorithm so that no states need storing
+    pub fn cycle(&self, start: ItemState) -> ItemCycle {
+        let step = |state| self.round(state, |_| ());
+        // Search in doubling windows for the length of the cycle
+        let (mut power, mut length) = (1, 1);
+        let mut tortoise = start;
+        let mut hare = step(start);
+        while tortoise != hare {
+            if power == length {
+                tortoise = hare;
+                power *= 2;
+                length = 0;
+            }
+            hare = step(hare);
+            length += 1;
+        }
+        // Then walk two items that length apart until they meet where the cycle starts
+        let (mut tortoise, mut hare) = (start, start);
+        for _ in 0..length {
+            hare = step(hare);
+        }
+        let mut lead_in = 0;
+        while tortoise != hare {
+            tortoise = step(tortoise);
+            hare = step(hare);
+            lead_in += 1;
+        }
+        ItemCycle {
+            start,
+            lead_in,
+            length,
+        }
+    }
+
+    /// Add the inspections of one item over `rounds` rounds to `counts`
+    ///
+    /// Panics if a count overflows.
+    pub fn add_inspections(&self, start: ItemState, rounds: usize, counts: &mut [usize]) {
+        let cycle = self.cycle(start);
+        let mut state = start;
+        let mut count = |monkey: usize| counts[monkey] += 1;
+        let lead_in = rounds.min(cycle.lead_in);
+        for _ in 0..lead_in {
+            state = self.round(state, &mut count);
+        }
+        let (laps, rest) = (
+            (rounds - lead_in) / cycle.length,
+            (rounds - lead_in) % cycle.length,
+        );
+        for _ in 0..rest {
+            state = self.round(state, &mut count);
+        }
+        if laps > 0 {
+            // Going round from anywhere on the cycle inspects the same
+            let mut lap = vec![0usize; counts.len()];
+            for _ in 0..cycle.length {
+                state = self.round(state, |monkey| lap[monkey] += 1);
+            }
+            for (count, per_lap) in counts.iter_mut().zip(lap) {
+                *count = per_lap
+                    .checked_mul(laps)
+                    .and_then(|total| total.checked_add(*count))
+                    .expect("inspection count overflows");
+            }
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Number of items each monkey inspects over `rounds` rounds, keeping worry levels modulo the
+/// divisors, without stepping through every round
+pub fn fast_forward(monkeys: &[Monkey], rounds: usize) -> Result<Vec<usize>, AnalysisError> {
+    let analyser = Analyser::new(monkeys)?;
+    let mut counts = vec![0; monkeys.len()];
+    for item in analyser.items() {
+        analyser.add_inspections(item, rounds, &mut counts);
+    }
+    Ok(counts)
+}
@@ -0,0 +1,364 @@
+//! Arithmetic on `old` from the `Operation: new = ...` lines.
+//!
+//! Expressions support `+ - * / %` with the usual precedence, parentheses and `old` anywhere,
+//! e.g. `(old + 3) * old % 17`.
+use std::fmt::{self, Display};
+
+use aoc_utils::{parse_token, ParseError};
+
+/// A binary arithmetic operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+impl Op {
+    fn from_symbol(c: char) -> Option<Self> {
+        match c {
+            '+' => Some(Self::Add),
+            '-' => Some(Self::Sub),
+            '*' => Some(Self::Mul),
+            '/' => Some(Self::Div),
+            '%' => Some(Self::Rem),
+            _ => None,
+        }
+    }
+
+    fn symbol(&self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Sub => '-',
+            Self::Mul => '*',
+            Self::Div => '/',
+            Self::Rem => '%',
+        }
+    }
+
+    /// Operators that bind tighter have a higher precedence
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Add | Self::Sub => 1,
+            Self::Mul | Self::Div | Self::Rem => 2,
+        }
+    }
+}
+
+/// A parsed expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// The worry level before the inspection
+    Old,
+    Value(usize),
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+impl Expr {
+    /// Parse `text`, a slice of `line`, so errors can point at the offending token
+    pub fn parse(line: &str, text: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            line,
+            tokens: tokenize(line, text)?,
+            pos: 0,
+        };
+        let expr = parser.expression(0)?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some((_, token)) => Err(ParseError::at_token(
+                line,
+                token,
+                format!("expected an operator but found `{}`", token),
+            )),
+        }
+    }
+
+    /// Value of the expression for the given `old`
+    ///
+    /// Fails if the result doesn't fit, goes below zero or divides by zero. Unlike
+    /// `eval_residue`, subtraction doesn't wrap round, as a worry level can't be negative.
+    pub fn eval(&self, old: usize) -> Result<usize, EvalError> {
+        match self {
+            Self::Old => Ok(old),
+            Self::Value(val) => Ok(*val),
+            Self::Binary(lhs, op, rhs) => {
+                let (lhs, rhs) = (lhs.eval(old)?, rhs.eval(old)?);
+                match op {
+                    Op::Add => lhs.checked_add(rhs),
+                    Op::Sub => lhs.checked_sub(rhs),
+                    Op::Mul => lhs.checked_mul(rhs),
+                    Op::Div => lhs.checked_div(rhs),
+                    Op::Rem => lhs.checked_rem(rhs),
+                }
+                .ok_or(EvalError { lhs, op: *op, rhs })
+            }
+        }
+    }
+
+    /// Value of the expression modulo `modulus`, knowing only `old` modulo `modulus`
+    ///
+    /// Addition, subtraction and multiplication carry over to remainders, so this is `None`
+    /// only when the expression divides. Subtraction wraps round modulo `modulus` rather than
+    /// going below zero, and sums and products are worked out in `u128`, so any `usize`
+    /// modulus is fine.
+    pub fn eval_residue(&self, old: usize, modulus: usize) -> Option<usize> {
+        Some(match self {
+            Self::Old => old % modulus,
+            Self::Value(val) => val % modulus,
+            Self::Binary(lhs, op, rhs) => {
+                let lhs = lhs.eval_residue(old, modulus)? as u128;
+                let rhs = rhs.eval_residue(old, modulus)? as u128;
+                let wide = modulus as u128;
+                // Each result is below `modulus`, so it fits back in a `usize`
+                (match op {
+                    Op::Add => (lhs + rhs) % wide,
+                    // Add the complement so this can't go below zero
+                    Op::Sub => (lhs + wide - rhs) % wide,
+                    Op::Mul => (lhs * rhs) % wide,
+                    Op::Div | Op::Rem => return None,
+                }) as usize
+            }
+        })
+    }
+
+    /// Whether `eval_residue` can evaluate this expression
+    pub fn is_modular(&self) -> bool {
+        match self {
+            Self::Old | Self::Value(_) => true,
+            Self::Binary(_, Op::Div | Op::Rem, _) => false,
+            Self::Binary(lhs, _, rhs) => lhs.is_modular() && rhs.is_modular(),
+        }
+    }
+}
+
+impl Display for Expr {
+    /// Writes the expression with only the parentheses it needs
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Old => write!(f, "old"),
+            Self::Value(val) => write!(f, "{}", val),
+            Self::Binary(lhs, op, rhs) => {
+                let needs_parens = |expr: &Expr, right: bool| match expr {
+                    Self::Binary(_, inner, _) => {
+                        inner.precedence() < op.precedence()
+                            || (right && inner.precedence() == op.precedence())
+                    }
+                    _ => false,
+                };
+                for (expr, right) in [(lhs, false), (rhs, true)] {
+                    if right {
+                        write!(f, " {} ", op.symbol())?;
+                    }
+                    if needs_parens(expr, right) {
+                        write!(f, "({})", expr)?;
+                    } else {
+                        write!(f, "{}", expr)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// An operation in an expression that has no worry level as its result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvalError {
+    pub lhs: usize,
+    pub op: Op,
+    pub rhs: usize,
+}
+impl Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let why = match self.op {
+            Op::Add | Op::Mul => "is too large",
+            Op::Sub => "is below zero",
+            Op::Div | Op::Rem => "divides by zero",
+        };
+        write!(f, "{} {} {} {}", self.lhs, self.op.symbol(), self.rhs, why)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Old,
+    Number,
+    Op(Op),
+    Open,
+    Close,
+}
+
+/// Split `text` into tokens, each alongside its slice of `line`
+fn tokenize<'a>(line: &'a str, text: &'a str) -> Result<Vec<(Token, &'a str)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let token = if c.is_whitespace() {
+            continue;
+        } else if c.is_ascii_alphanumeric() {
+            // Take the whole word
+            let mut end = start + 1;
+            while let Some((idx, c)) = chars.next_if(|(_, c)| c.is_ascii_alphanumeric()) {
+                end = idx + c.len_utf8();
+            }
+            let word = &text[start..end];
+            if word == "old" {
+                Token::Old
+            } else if word.bytes().all(|b| b.is_ascii_digit()) {
+                Token::Number
+            } else {
+                return Err(ParseError::at_token(
+                    line,
+                    word,
+                    format!("expected `old` or a number but found `{}`", word),
+                ));
+            }
+        } else if let Some(op) = Op::from_symbol(c) {
+            Token::Op(op)
+        } else if c == '(' {
+            Token::Open
+        } else if c == ')' {
+            Token::Close
+        } else {
+            return Err(ParseError::at_token(
+                line,
+                &text[start..start + c.len_utf8()],
+                format!("unexpected character `{}`", c),
+            ));
+        };
+        let end = chars.peek().map_or(text.len(), |(idx, _)| *idx);
+        tokens.push((token, text[start..end].trim_end()));
+    }
+    Ok(tokens)
+}
+
+/// Precedence climbing over the tokens of one expression
+struct Parser<'a> {
+    line: &'a str,
+    tokens: Vec<(Token, &'a str)>,
+    pos: usize,
+}
+impl<'a> Parser<'a> {
+    /// Parse operators binding at least as tightly as `min_precedence`
+    fn expression(&mut self, min_precedence: u8) -> Result<Expr, ParseError> {
+        let mut lhs = self.operand()?;
+        while let Some(&(Token::Op(op), _)) = self.tokens.get(self.pos) {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.pos += 1;
+            let rhs_start = self.tokens.get(self.pos).map_or("", |(_, text)| *text);
+            // Only tighter operators go on the right, so equal ones associate to the left
+            let rhs = self.expression(op.precedence() + 1)?;
+            if matches!(op, Op::Div | Op::Rem) && rhs == Expr::Value(0) {
+                return Err(ParseError::at_token(
+                    self.line,
+                    rhs_start,
+                    format!("cannot use `{}` with zero", op.symbol()),
+                ));
+            }
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn operand(&mut self) -> Result<Expr, ParseError> {
+        let Some(&(token, text)) = self.tokens.get(self.pos) else {
+            return Err(ParseError::missing(self.line, "`old`, a number or `(`"));
+        };
+        self.pos += 1;
+        match token {
+            Token::Old => Ok(Expr::Old),
+            Token::Number => Ok(Expr::Value(parse_token(self.line, text)?)),
+            Token::Open => {
+                let expr = self.expression(0)?;
+                match self.tokens.get(self.pos) {
+                    Some((Token::Close, _)) => {
+                        self.pos += 1;
+                        Ok(expr)
+                    }
+                    Some((_, text)) => Err(ParseError::at_token(
+                        self.line,
+                        text,
+                        format!("expected `)` but found `{}`", text),
+                    )),
+                    None => Err(ParseError::missing(self.line, "`)`")),
+                }
+            }
+            Token::Op(_) | Token::Close => Err(ParseError::at_token(
+                self.line,
+                text,
+                format!("expected `old`, a number or `(` but found `{}`", text),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Expr, ParseError> {
+        Expr::parse(text, text)
+    }
+
+    #[test]
+    fn test_precedence() {
+        let expr = parse("2 * old + (old - 1) * 3 % 5").unwrap();
+        assert_eq!(expr.to_string(), "2 * old + (old - 1) * 3 % 5");
+        assert_eq!(expr.eval(4), Ok(8 + 9 % 5));
+        assert_eq!(parse("10 - 4 - 3").unwrap().eval(0), Ok(3));
+        assert_eq!(parse("old-(old-1)").unwrap().to_string(), "old - (old - 1)");
+        assert_eq!(parse("((old))").unwrap(), Expr::Old);
+    }
+
+    #[test]
+    fn test_residues() {
+        let expr = parse("19 * old * old - old + 7").unwrap();
+        assert!(expr.is_modular());
+        for old in [0, 1, 5, 79, 1000] {
+            for modulus in [2, 3, 13, 23] {
+                assert_eq!(
+                    expr.eval_residue(old % modulus, modulus),
+                    Some(expr.eval(old).unwrap() % modulus)
+                );
+            }
+        }
+        assert!(!parse("(old + 1) / 3").unwrap().is_modular());
+        // Past 2^32 the product of two remainders no longer fits in 64 bits
+        let modulus = (1 << 40) + 15;
+        let residue = parse("old * old - 1")
+            .unwrap()
+            .eval_residue(modulus - 2, modulus);
+        assert_eq!(residue, Some(3));
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = parse("old * (3 + ").unwrap_err();
+        assert_eq!(err.message, "expected `old`, a number or `(`");
+        let err = parse("old ^ 2").unwrap_err();
+        assert_eq!(
+            (err.column, err.message.as_str()),
+            (5, "unexpected character `^`")
+        );
+        let err = parse("old 2").unwrap_err();
+        assert_eq!(err.column, 5);
+        let err = parse("new + 1").unwrap_err();
+        assert_eq!(err.column, 1);
+        let err = parse("old % (0)").unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (7, "("));
+        assert_eq!(err.message, "cannot use `%` with zero");
+        assert!(parse("old / (0 + 1)").is_ok());
+    }
+
+    #[test]
+    fn test_eval_errors() {
+        let err = parse("old - 5").unwrap().eval(3).unwrap_err();
+        assert_eq!(err.to_string(), "3 - 5 is below zero");
+        let err = parse("old * old").unwrap().eval(usize::MAX).unwrap_err();
+        assert_eq!((err.op, err.rhs), (Op::Mul, usize::MAX));
+        let err = parse("10 / (old - 4)").unwrap().eval(4).unwrap_err();
+        assert_eq!(err.to_string(), "10 / 0 divides by zero");
+    }
+}
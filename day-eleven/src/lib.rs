@@ -0,0 +1,694 @@
+//! Solutions for part one and part two
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display},
+    io::BufRead,
+    rc::Rc,
+};
+
+use aoc_utils::{next_token, parse_token, try_read_lines, ParseError, Solution};
+
+pub mod cycles;
+pub mod expression;
+pub mod trace;
+use expression::{EvalError, Expr};
+use trace::{Observer, Throw, WorrySnapshot};
+
+/// Parse each seven line monkey definition with `f`, tagging errors with their line in the file
+fn parse_monkeys<T, F>(lines: &[String], f: F) -> Result<Vec<T>, ParseError>
+where
+    F: Fn(&[String]) -> Result<T, ParseError>,
+{
+    lines
+        .chunks(7)
+        .enumerate()
+        .map(|(idx, lines)| {
+            f(lines).map_err(|mut e| {
+                e.line += idx * 7;
+                e
+            })
+        })
+        .collect()
+}
+
+fn parse_input(lines: &[String]) -> Result<Vec<Monkey>, ParseError> {
+    let monkeys = parse_monkeys(lines, Monkey::from_lines)?;
+    if monkeys.len() < 2 {
+        return Err(ParseError::input(format!(
+            "expected at least two monkeys but found {}",
+            monkeys.len()
+        )));
+    }
+    // Only now is it known how many monkeys there are to throw to
+    for (idx, monkey) in monkeys.iter().enumerate() {
+        let (if_true, if_false) = monkey.monkey_throw_idxs;
+        for (offset, target) in [(4, if_true), (5, if_false)] {
+            let message = if target >= monkeys.len() {
+                format!(
+                    "cannot throw to monkey {} as there are only monkeys 0 to {}",
+                    target,
+                    monkeys.len() - 1
+                )
+            } else if target == idx {
+                // It would inspect the same item again and again within its turn
+                format!("monkey {} cannot throw to itself", idx)
+            } else {
+                continue;
+            };
+            let line_idx = idx * 7 + offset;
+            let line = &lines[line_idx];
+            let token = line.split_whitespace().last().unwrap_or("");
+            return Err(ParseError::at_token(line, token, message).with_line(line_idx + 1));
+        }
+    }
+    Ok(monkeys)
+}
+
+type ReturnType = usize;
+type VectorType = Monkey;
+
+/// Solution for day eleven
+pub struct DayEleven;
+impl Solution for DayEleven {
+    type Input = Vec<VectorType>;
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        parse_input(&try_read_lines(reader)?)
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        part_one_internal(input)
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        part_two_internal(input)
+    }
+}
+
+/// Get line `idx` of a monkey definition
+fn monkey_line<'a>(input: &'a [String], idx: usize, what: &str) -> Result<&'a str, ParseError> {
+    input.get(idx).map(|line| line.as_str()).ok_or_else(|| {
+        let last = input.last().map(|line| line.as_str()).unwrap_or("");
+        ParseError::missing(last, &format!("a line with {}", what)).with_line(input.len())
+    })
+}
+
+/// Parse the text after the colon on line `idx` with `f`
+fn after_colon<'a, T, F>(input: &'a [String], idx: usize, what: &str, f: F) -> Result<T, ParseError>
+where
+    F: FnOnce(&'a str, &'a str) -> Result<T, ParseError>,
+{
+    let line = monkey_line(input, idx, what)?;
+    let mut parts = line.splitn(2, ':');
+    let _ = parts.next();
+    let rest = next_token(line, &mut parts, &format!("`:` followed by {}", what))
+        .map_err(|e| e.with_line(idx + 1))?;
+    f(line, rest).map_err(|e| e.with_line(idx + 1))
+}
+
+/// Parse the comma separated starting items on line `idx`
+fn parse_items(input: &[String], idx: usize) -> Result<Vec<usize>, ParseError> {
+    after_colon(input, idx, "starting items", |line, rest| {
+        rest.split(',')
+            .map(|item| parse_token(line, item.trim()))
+            .collect()
+    })
+}
+
+/// Parse the expression on the right of `Operation: new = ...`
+fn parse_operation(input: &[String], idx: usize) -> Result<Expr, ParseError> {
+    after_colon(input, idx, "an operation", |line, rest| {
+        let mut parts = rest.splitn(2, '=').skip(1);
+        Expr::parse(line, next_token(line, &mut parts, "`new = ...`")?)
+    })
+}
+
+/// Parse the number ending line `idx`
+fn parse_last_number(input: &[String], idx: usize, what: &str) -> Result<usize, ParseError> {
+    let line = monkey_line(input, idx, what)?;
+    let mut words = line.split_whitespace().rev();
+    next_token(line, &mut words, what)
+        .and_then(|word| parse_token(line, word))
+        .map_err(|e| e.with_line(idx + 1))
+}
+
+#[derive(Debug)]
+pub struct Monkey {
+    items: Vec<usize>,
+    operation: Expr,
+    diviser: usize,
+    monkey_throw_idxs: (usize, usize),
+}
+impl Monkey {
+    /// Take the whole monkey definition
+    fn from_lines(input: &[String]) -> Result<Self, ParseError> {
+        // Example:
+        // Monkey 0:
+        //   Starting items: 79, 98
+        //   Operation: new = old * 19
+        //   Test: divisible by 23
+        //     If true: throw to monkey 2
+        //     If false: throw to monkey 3
+
+        // First line doesn't matter
+
+        // Get starting items
+        let items = parse_items(input, 1)?;
+
+        // Parse operation
+        let operation = parse_operation(input, 2)?;
+
+        // Test
+        let diviser = parse_last_number(input, 3, "the test divisor")?;
+        if diviser == 0 {
+            let line = &input[3];
+            return Err(ParseError::at_column(
+                line,
+                line.chars().count(),
+                "0",
+                "cannot test for divisibility by zero",
+            )
+            .with_line(4));
+        }
+
+        // Monkey throw indeces
+        let true_idx = parse_last_number(input, 4, "the monkey to throw to if true")?;
+        let false_idx = parse_last_number(input, 5, "the monkey to throw to if false")?;
+
+        Ok(Self {
+            items,
+            operation,
+            diviser,
+            monkey_throw_idxs: (true_idx, false_idx),
+        })
+    }
+}
+
+/// How worry levels are kept manageable after each inspection
+pub enum WorryPolicy {
+    /// Divide by `k`, rounding down, as in part one
+    Divide(usize),
+    /// Only track the remainder against every monkey's divisor, as in part two
+    Modular,
+    /// Leave worry levels alone, so simulating fails once one no longer fits in a `usize`
+    None,
+    /// Apply any other function to the worry level
+    Custom(Box<dyn Fn(usize) -> usize>),
+}
+
+/// An item's worry level, in whatever form the policy needs
+trait Worry: Sized {
+    fn apply(&mut self, operation: &Expr) -> Result<(), EvalError>;
+    /// Whether the worry level is a multiple of `diviser`, which is `moduli(..)[modulus_idx]`
+    fn is_divisible(&self, diviser: usize, modulus_idx: usize) -> bool;
+    fn relieve(self, policy: &WorryPolicy) -> Self;
+    fn snapshot(&self) -> WorrySnapshot<'_>;
+}
+
+impl Worry for usize {
+    fn apply(&mut self, operation: &Expr) -> Result<(), EvalError> {
+        *self = operation.eval(*self)?;
+        Ok(())
+    }
+
+    fn is_divisible(&self, diviser: usize, _modulus_idx: usize) -> bool {
+        self.is_multiple_of(diviser)
+    }
+
+    fn relieve(self, policy: &WorryPolicy) -> Self {
+        match policy {
+            WorryPolicy::Divide(k) => self / k,
+            WorryPolicy::Modular | WorryPolicy::None => self,
+            WorryPolicy::Custom(f) => f(self),
+        }
+    }
+
+    fn snapshot(&self) -> WorrySnapshot<'_> {
+        WorrySnapshot::Level(*self)
+    }
+}
+
+/// A worry level tracked only by its remainder against each test divisor
+///
+/// The divisors are the ones the parsed monkeys test against, so the real value never needs to
+/// be kept and can grow without bound.
+struct WorryLevel {
+    moduli: Rc<[usize]>,
+    remainders: Vec<usize>,
+}
+impl WorryLevel {
+    fn new(value: usize, moduli: &Rc<[usize]>) -> Self {
+        let remainders = moduli.iter().map(|x| value % x).collect();
+        Self {
+            moduli: Rc::clone(moduli),
+            remainders,
+        }
+    }
+}
+
+impl Worry for WorryLevel {
+    fn apply(&mut self, operation: &Expr) -> Result<(), EvalError> {
+        for (diviser, remainder) in std::iter::zip(self.moduli.iter(), self.remainders.iter_mut()) {
+            *remainder = operation
+                .eval_residue(*remainder, *diviser)
+                .expect("operations are checked before tracking remainders");
+        }
+        Ok(())
+    }
+
+    fn is_divisible(&self, _diviser: usize, modulus_idx: usize) -> bool {
+        self.remainders[modulus_idx] == 0
+    }
+
+    /// The remainders are already as small as they can be
+    fn relieve(self, _policy: &WorryPolicy) -> Self {
+        self
+    }
+
+    fn snapshot(&self) -> WorrySnapshot<'_> {
+        WorrySnapshot::Residues(&self.remainders)
+    }
+}
+
+/// Every distinct divisor the monkeys test against
+fn moduli(monkeys: &[Monkey]) -> Rc<[usize]> {
+    let mut moduli: Vec<usize> = monkeys.iter().map(|monkey| monkey.diviser).collect();
+    moduli.sort_unstable();
+    moduli.dedup();
+    moduli.into()
+}
+
+/// The monkeys passing items around, one round at a time
+struct Simulation<'a, W> {
+    monkeys: &'a [Monkey],
+    policy: &'a WorryPolicy,
+    /// Each item's number alongside its worry level
+    items: Vec<VecDeque<(usize, W)>>,
+    /// Where each monkey's divisor is in `moduli(monkeys)`
+    modulus_idxs: Vec<usize>,
+    rounds_done: usize,
+    n_items_counted: Vec<usize>,
+}
+impl<'a, W: Worry> Simulation<'a, W> {
+    fn new<F: Fn(usize) -> W>(monkeys: &'a [Monkey], policy: &'a WorryPolicy, f: F) -> Self {
+        let mut next_id = 0;
+        let items = monkeys
+            .iter()
+            .map(|monkey| {
+                monkey
+                    .items
+                    .iter()
+                    .map(|item| {
+                        next_id += 1;
+                        (next_id - 1, f(*item))
+                    })
+                    .collect()
+            })
+            .collect();
+        let moduli = moduli(monkeys);
+        let modulus_idxs = monkeys
+            .iter()
+            .map(|monkey| moduli.partition_point(|&modulus| modulus < monkey.diviser))
+            .collect();
+        Self {
+            monkeys,
+            policy,
+            items,
+            modulus_idxs,
+            rounds_done: 0,
+            n_items_counted: vec![0; monkeys.len()],
+        }
+    }
+
+    fn round(&mut self, observer: &mut dyn Observer) -> Result<(), SimulationError> {
+        let round = self.rounds_done + 1;
+        let mut inspected = vec![0; self.monkeys.len()];
+        for (idx, monkey) in self.monkeys.iter().enumerate() {
+            while let Some((id, mut item)) = self.items[idx].pop_front() {
+                // Apply the monkeys modifier
+                item.apply(&monkey.operation)
+                    .map_err(|error| SimulationError::Operation {
+                        round,
+                        monkey: idx,
+                        error,
+                    })?;
+                let item = item.relieve(self.policy);
+                // Determine if divisible by diviser
+                let target = if item.is_divisible(monkey.diviser, self.modulus_idxs[idx]) {
+                    monkey.monkey_throw_idxs.0
+                } else {
+                    monkey.monkey_throw_idxs.1
+                };
+                observer.throw(&Throw {
+                    round,
+                    from: idx,
+                    to: target,
+                    item: id,
+                    worry: item.snapshot(),
+                });
+                self.items[target].push_back((id, item));
+
+                inspected[idx] += 1;
+            }
+        }
+        for (total, count) in self.n_items_counted.iter_mut().zip(&inspected) {
+            *total += count;
+        }
+        self.rounds_done = round;
+        observer.round_end(round, &inspected, &self.n_items_counted);
+        Ok(())
+    }
+}
+
+impl<'a> Simulation<'a, WorryLevel> {
+    /// Track every worry level by its remainders, which needs operations that don't divide
+    fn modular(monkeys: &'a [Monkey]) -> Result<Self, NotModular> {
+        if let Some(monkey) = monkeys
+            .iter()
+            .position(|monkey| !monkey.operation.is_modular())
+        {
+            return Err(NotModular { monkey });
+        }
+        let moduli = moduli(monkeys);
+        Ok(Self::new(monkeys, &WorryPolicy::Modular, |item| {
+            WorryLevel::new(item, &moduli)
+        }))
+    }
+}
+
+/// A monkey whose operation divides, so it can't be worked out from remainders alone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotModular {
+    pub monkey: usize,
+}
+impl Display for NotModular {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "monkey {} divides its worry level, which can't be tracked by remainders",
+            self.monkey
+        )
+    }
+}
+
+/// Why the monkeys can't be simulated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationError {
+    NotModular(NotModular),
+    /// A monkey's operation has no worry level as its result
+    Operation {
+        round: usize,
+        monkey: usize,
+        error: EvalError,
+    },
+}
+impl Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotModular(e) => write!(f, "{}", e),
+            Self::Operation {
+                round,
+                monkey,
+                error,
+            } => write!(
+                f,
+                "in round {} monkey {} has no worry level as {}",
+                round, monkey, error
+            ),
+        }
+    }
+}
+impl From<NotModular> for SimulationError {
+    fn from(e: NotModular) -> Self {
+        Self::NotModular(e)
+    }
+}
+
+/// Number of items each monkey inspects over `rounds` rounds
+pub fn count_inspections(
+    monkeys: &[Monkey],
+    rounds: usize,
+    policy: &WorryPolicy,
+) -> Result<Vec<usize>, SimulationError> {
+    trace(monkeys, rounds, policy, &mut ())
+}
+
+/// Like `count_inspections`, showing every throw and the end of every round to `observer`
+pub fn trace(
+    monkeys: &[Monkey],
+    rounds: usize,
+    policy: &WorryPolicy,
+    observer: &mut dyn Observer,
+) -> Result<Vec<usize>, SimulationError> {
+    fn run<W: Worry>(
+        mut simulation: Simulation<W>,
+        rounds: usize,
+        observer: &mut dyn Observer,
+    ) -> Result<Vec<usize>, SimulationError> {
+        for _round in 0..rounds {
+            simulation.round(observer)?;
+        }
+        Ok(simulation.n_items_counted)
+    }
+
+    match policy {
+        WorryPolicy::Modular => run(Simulation::modular(monkeys)?, rounds, observer),
+        _ => run(
+            Simulation::new(monkeys, policy, |item| item),
+            rounds,
+            observer,
+        ),
+    }
+}
+
+/// Product of the two largest inspection counts, or `None` with fewer than two monkeys
+pub fn monkey_business(mut inspected_items: Vec<usize>) -> Option<usize> {
+    inspected_items.sort();
+    inspected_items.reverse();
+    match inspected_items[..] {
+        [first, second, ..] => Some(first * second),
+        _ => None,
+    }
+}
+
+fn part_one_internal(monkeys: Vec<VectorType>) -> Result<ReturnType, ParseError> {
+    let counts = count_inspections(&monkeys, 20, &WorryPolicy::Divide(3))
+        .map_err(|e| ParseError::input(e.to_string()))?;
+    Ok(monkey_business(counts).expect("parsing checks there are at least two monkeys"))
+}
+
+fn part_two_internal(monkeys: Vec<VectorType>) -> Result<ReturnType, ParseError> {
+    // Tracking remainders fails up front if any operation divides
+    let counts = count_inspections(&monkeys, 10000, &WorryPolicy::Modular)
+        .map_err(|e| ParseError::input(e.to_string()))?;
+    Ok(monkey_business(counts).expect("parsing checks there are at least two monkeys"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cycles::{fast_forward, Analyser, AnalysisError};
+    use trace::{CsvTrace, JsonLinesTrace};
+
+    fn input() -> &'static str {
+        "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+
+"
+    }
+
+    fn monkeys() -> Vec<Monkey> {
+        DayEleven::parse(input().as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_one() {
+        assert_eq!(part_one_internal(monkeys()), Ok(10605));
+    }
+
+    #[test]
+    fn test_two() {
+        assert_eq!(part_two_internal(monkeys()), Ok(2713310158));
+    }
+
+    #[test]
+    fn test_policies() {
+        let monkeys = monkeys();
+        assert_eq!(
+            count_inspections(&monkeys, 20, &WorryPolicy::Modular),
+            Ok(vec![99, 97, 8, 103])
+        );
+        // Dividing by one is the same as doing nothing
+        assert_eq!(
+            count_inspections(&monkeys, 10, &WorryPolicy::Custom(Box::new(|x| x))),
+            count_inspections(&monkeys, 10, &WorryPolicy::Divide(1))
+        );
+        assert_eq!(
+            count_inspections(&monkeys, 10, &WorryPolicy::None),
+            count_inspections(&monkeys, 10, &WorryPolicy::Modular)
+        );
+        // Squaring soon takes a worry level past what fits
+        match count_inspections(&monkeys, 20, &WorryPolicy::None) {
+            Err(SimulationError::Operation { round, error, .. }) => {
+                assert!(round > 10);
+                assert!(error.to_string().ends_with("is too large"));
+            }
+            other => panic!("expected an overflow but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_expressions() {
+        let variant = input()
+            .replace("new = old * 19", "new = 19 * old")
+            .replace("new = old + 6", "new = (old + 12) - 6")
+            .replace("new = old * old", "new = old * (old - 0)");
+        let monkeys = DayEleven::parse(variant.as_bytes()).unwrap();
+        assert_eq!(part_one_internal(monkeys), Ok(10605));
+        let negative = input().replace("new = old + 6", "new = old - 60");
+        let monkeys = DayEleven::parse(negative.as_bytes()).unwrap();
+        assert_eq!(
+            part_one_internal(monkeys).unwrap_err().message,
+            "in round 1 monkey 1 has no worry level as 54 - 60 is below zero"
+        );
+
+        // Part one can divide, but part two can't track remainders through it
+        let input = input().replace("new = old + 3", "new = old + 3 % 2");
+        let monkeys = DayEleven::parse(input.as_bytes()).unwrap();
+        assert_eq!(
+            count_inspections(&monkeys, 1, &WorryPolicy::Modular),
+            Err(SimulationError::NotModular(NotModular { monkey: 3 }))
+        );
+        assert!(DayEleven::part_one(DayEleven::parse(input.as_bytes()).unwrap()).is_ok());
+        let err = part_two_internal(monkeys).unwrap_err();
+        assert_eq!(
+            err.message,
+            "monkey 3 divides its worry level, which can't be tracked by remainders"
+        );
+    }
+
+    #[test]
+    fn test_zero_divisor() {
+        let input = input().replace("divisible by 19", "divisible by 0");
+        let err = DayEleven::parse(input.as_bytes()).unwrap_err();
+        assert_eq!((err.line, err.column), (11, 22));
+    }
+
+    #[test]
+    fn test_throw_targets() {
+        let input = input().replace("If true: throw to monkey 1", "If true: throw to monkey 9");
+        let err = DayEleven::parse(input.as_bytes()).unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (19, 30, "9"));
+        let one_monkey = input.split("\n\n").next().unwrap();
+        let err = DayEleven::parse(one_monkey.as_bytes()).unwrap_err();
+        assert_eq!(err.message, "expected at least two monkeys but found 1");
+        assert_eq!(monkey_business(vec![4]), None);
+        assert_eq!(monkey_business(vec![4, 1, 5]), Some(20));
+    }
+
+    #[test]
+    fn test_throws_to_itself() {
+        let input = input().replace("monkey 3", "monkey 2");
+        let err = DayEleven::parse(input.as_bytes()).unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.message.as_str()),
+            (20, 31, "monkey 2 cannot throw to itself")
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let monkeys = monkeys();
+        let (mut throws, mut rounds) = (Vec::new(), Vec::new());
+        let mut csv = CsvTrace::new(&mut throws, &mut rounds).unwrap();
+        let counts = trace(&monkeys, 2, &WorryPolicy::Divide(3), &mut csv).unwrap();
+        csv.finish().unwrap();
+        let throws = String::from_utf8(throws).unwrap();
+        let mut lines = throws.lines();
+        assert_eq!(lines.next(), Some("round,from,to,item,worry"));
+        // From the walkthrough of the first round in the puzzle
+        assert_eq!(lines.next(), Some("1,0,3,0,500"));
+        assert_eq!(lines.next(), Some("1,0,3,1,620"));
+        assert_eq!(lines.next(), Some("1,1,0,2,20"));
+        assert_eq!(lines.count(), counts.iter().sum::<usize>() - 3);
+        let rounds = String::from_utf8(rounds).unwrap();
+        assert_eq!(rounds.lines().nth(1), Some("1,0,2,2"));
+        assert_eq!(
+            rounds.lines().last(),
+            Some(format!("2,3,{},{}", counts[3] - 5, counts[3]).as_str())
+        );
+
+        let mut jsonl = Vec::new();
+        let mut json = JsonLinesTrace::new(&mut jsonl);
+        trace(&monkeys, 1, &WorryPolicy::Modular, &mut json).unwrap();
+        json.finish().unwrap();
+        let jsonl = String::from_utf8(jsonl).unwrap();
+        // 79 * 19 = 1501 against 13, 17, 19 and 23
+        assert_eq!(
+            jsonl.lines().next(),
+            Some(r#"{"type":"throw","round":1,"from":0,"to":3,"item":0,"residues":[6,5,0,6]}"#)
+        );
+        assert_eq!(
+            jsonl.lines().last(),
+            Some(r#"{"type":"round","round":1,"inspected":[2,4,3,6],"totals":[2,4,3,6]}"#)
+        );
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        let monkeys = monkeys();
+        for rounds in [0, 1, 20, 1000, 10000] {
+            assert_eq!(
+                fast_forward(&monkeys, rounds).unwrap(),
+                count_inspections(&monkeys, rounds, &WorryPolicy::Modular).unwrap()
+            );
+        }
+        let analyser = Analyser::new(&monkeys).unwrap();
+        let items = analyser.items();
+        assert_eq!(items.len(), 10);
+        let longest = items
+            .iter()
+            .map(|item| analyser.cycle(*item))
+            .map(|cycle| cycle.lead_in + cycle.length)
+            .max()
+            .unwrap();
+        assert!(longest < 1_000_000);
+        // Far beyond anything worth stepping through, with every item inspected each round
+        let counts = fast_forward(&monkeys, 1_000_000_000).unwrap();
+        assert!(counts.iter().sum::<usize>() >= 10 * 1_000_000_000);
+
+        let input = input().replace("old + 6", "old / 2");
+        let monkeys = DayEleven::parse(input.as_bytes()).unwrap();
+        assert_eq!(
+            fast_forward(&monkeys, 1).unwrap_err(),
+            AnalysisError::NotModular(NotModular { monkey: 1 })
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Watching the monkeys throw items, and writing what they did out as CSV or JSON lines.
+use std::io::{self, Write};
+
+/// An item's worry level as the simulation holds it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorrySnapshot<'a> {
+    Level(usize),
+    /// Remainders against each distinct test divisor, smallest divisor first
+    Residues(&'a [usize]),
+}
+
+/// One item thrown from one monkey to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Throw<'a> {
+    /// Round number, counting from 1
+    pub round: usize,
+    pub from: usize,
+    pub to: usize,
+    /// Items are numbered from 0 in the order the monkeys start with them
+    pub item: usize,
+    /// Worry level after the inspection and any relief
+    pub worry: WorrySnapshot<'a>,
+}
+
+/// Something watching the simulation, which by default ignores everything
+pub trait Observer {
+    fn throw(&mut self, _throw: &Throw) {}
+
+    /// Called after each round with the items each monkey inspected that round and in total
+    fn round_end(&mut self, _round: usize, _inspected: &[usize], _totals: &[usize]) {}
+}
+
+/// Watches nothing
+impl Observer for () {}
+
+/// Writes throws and rounds to separate CSV files
+///
+/// Residues are written separated by spaces. Observers can't fail, so the first error is kept
+/// for `finish`.
+pub struct CsvTrace<W: Write> {
+    throws: W,
+    rounds: W,
+    error: Option<io::Error>,
+}
+impl<W: Write> CsvTrace<W> {
+    pub fn new(mut throws: W, mut rounds: W) -> io::Result<Self> {
+        writeln!(throws, "round,from,to,item,worry")?;
+        writeln!(rounds, "round,monkey,inspected,total")?;
+        Ok(Self {
+            throws,
+            rounds,
+            error: None,
+        })
+    }
+
+    /// Flush both files and report the first error writing either
+    pub fn finish(mut self) -> io::Result<()> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        self.throws.flush()?;
+        self.rounds.flush()
+    }
+
+    fn keep_error(&mut self, result: io::Result<()>) {
+        if let Err(err) = result {
+            self.error.get_or_insert(err);
+        }
+    }
+}
+impl<W: Write> Observer for CsvTrace<W> {
+    fn throw(&mut self, throw: &Throw) {
+        let worry = match throw.worry {
+            WorrySnapshot::Level(level) => level.to_string(),
+            WorrySnapshot::Residues(residues) => join(residues, " "),
+        };
+        let result = writeln!(
+            self.throws,
+            "{},{},{},{},{}",
+            throw.round, throw.from, throw.to, throw.item, worry
+        );
+        self.keep_error(result);
+    }
+
+    fn round_end(&mut self, round: usize, inspected: &[usize], totals: &[usize]) {
+        for (monkey, (count, total)) in inspected.iter().zip(totals).enumerate() {
+            let result = writeln!(self.rounds, "{},{},{},{}", round, monkey, count, total);
+            self.keep_error(result);
+        }
+    }
+}
+
+/// Writes one JSON object per line for each throw and each round
+///
+/// Throws look like `{"type":"throw","round":1,"from":0,"to":3,"item":0,"worry":500}`, with
+/// `"residues":[...]` in place of `"worry"` when only remainders are kept, and rounds like
+/// `{"type":"round","round":1,"inspected":[2,4,3,6],"totals":[2,4,3,6]}`.
+pub struct JsonLinesTrace<W: Write> {
+    writer: W,
+    error: Option<io::Error>,
+}
+impl<W: Write> JsonLinesTrace<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            error: None,
+        }
+    }
+
+    /// Flush the output and report the first error writing it
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.error {
+            Some(err) => Err(err),
+            None => self.writer.flush(),
+        }
+    }
+
+    fn write_line(&mut self, line: String) {
+        if let Err(err) = writeln!(self.writer, "{}", line) {
+            self.error.get_or_insert(err);
+        }
+    }
+}
+impl<W: Write> Observer for JsonLinesTrace<W> {
+    fn throw(&mut self, throw: &Throw) {
+        let worry = match throw.worry {
+            WorrySnapshot::Level(level) => format!("\"worry\":{}", level),
+            WorrySnapshot::Residues(residues) => format!("\"residues\":[{}]", join(residues, ",")),
+        };
+        self.write_line(format!(
+            "{{\"type\":\"throw\",\"round\":{},\"from\":{},\"to\":{},\"item\":{},{}}}",
+            throw.round, throw.from, throw.to, throw.item, worry
+        ));
+    }
+
+    fn round_end(&mut self, round: usize, inspected: &[usize], totals: &[usize]) {
+        self.write_line(format!(
+            "{{\"type\":\"round\",\"round\":{},\"inspected\":[{}],\"totals\":[{}]}}",
+            round,
+            join(inspected, ","),
+            join(totals, ",")
+        ));
+    }
+}
+
+fn join(values: &[usize], separator: &str) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
@@ -0,0 +1,301 @@
+//! Solutions for part one and part two
+use std::{
+    fmt::{self, Display},
+    io::BufRead,
+};
+
+use aoc_utils::{next_token, parse_token, try_read_lines, ParseError, Solution};
+
+type ReturnType = Vec<char>;
+type VectorType = Instruction;
+/// Stacks of crates, bottom first
+type Crates = Vec<Vec<char>>;
+
+/// Solution for day five
+pub struct DayFive;
+impl Solution for DayFive {
+    type Input = (Crates, Vec<VectorType>);
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        parse_input(&try_read_lines(reader)?)
+    }
+
+    fn part_one((crates, input): Self::Input) -> Result<Self::PartOne, ParseError> {
+        Ok(part_one_internal(input, crates).expect("moves are validated while parsing"))
+    }
+
+    fn part_two((crates, input): Self::Input) -> Result<Self::PartTwo, ParseError> {
+        Ok(part_two_internal(input, crates).expect("moves are validated while parsing"))
+    }
+}
+
+/// Split the input into the starting crates and the moves, checking every move can be made
+fn parse_input(lines: &[String]) -> Result<(Crates, Vec<VectorType>), ParseError> {
+    let blank = lines
+        .iter()
+        .position(|line| line.is_empty())
+        .ok_or_else(|| {
+            ParseError::input("expected a blank line between the crate drawing and the moves")
+        })?;
+    let crates = parse_crates(&lines[..blank])?;
+
+    let mut instructions = Vec::new();
+    // Simulate on a copy so that a bad move is reported against its own line
+    let mut check = crates.clone();
+    for (idx, line) in lines.iter().enumerate().skip(blank + 1) {
+        if line.is_empty() {
+            continue;
+        }
+        let instruction = map_one(line).map_err(|e| e.with_line(idx + 1))?;
+        instruction
+            .apply(&mut check)
+            .map_err(|e| ParseError::at_column(line, 1, line, e.to_string()).with_line(idx + 1))?;
+        instructions.push(instruction);
+    }
+    Ok((crates, instructions))
+}
+
+/// Parse the drawing of the stacks, ending with the line of stack numbers
+///
+/// ```text
+///     [D]
+/// [N] [C]
+/// [Z] [M] [P]
+///  1   2   3
+/// ```
+fn parse_crates(drawing: &[String]) -> Result<Crates, ParseError> {
+    let (index_line, rows) = drawing
+        .split_last()
+        .ok_or_else(|| ParseError::input("expected a drawing of the crates").with_line(1))?;
+    let index_line_number = drawing.len();
+
+    // Stack numbers must count up from one
+    let mut n_stacks = 0;
+    for label in index_line.split_whitespace() {
+        let number: usize =
+            parse_token(index_line, label).map_err(|e| e.with_line(index_line_number))?;
+        if number != n_stacks + 1 {
+            return Err(ParseError::at_token(
+                index_line,
+                label,
+                format!("expected stack {} but found `{}`", n_stacks + 1, label),
+            )
+            .with_line(index_line_number));
+        }
+        n_stacks += 1;
+    }
+
+    // Fill the stacks from the bottom row up
+    let mut crates = vec![Vec::new(); n_stacks];
+    for (idx, row) in rows.iter().enumerate().rev() {
+        if !row.is_ascii() {
+            return Err(
+                ParseError::at_column(row, 1, row, "expected an ASCII drawing").with_line(idx + 1),
+            );
+        }
+        for (stack, offset) in (0..row.len()).step_by(4).enumerate() {
+            let cell = &row[offset..(offset + 3).min(row.len())];
+            let error =
+                |message: String| ParseError::at_token(row, cell, message).with_line(idx + 1);
+            let label = match cell.as_bytes() {
+                [b'[', label, b']'] if label.is_ascii_alphabetic() => *label as char,
+                _ if cell.trim().is_empty() => continue,
+                _ => {
+                    return Err(error(format!(
+                        "expected a crate like `[A]` but found `{}`",
+                        cell
+                    )))
+                }
+            };
+            let Some(stack_crates) = crates.get_mut(stack) else {
+                return Err(error(format!(
+                    "crate is past the last stack ({})",
+                    n_stacks
+                )));
+            };
+            if stack_crates.len() != rows.len() - 1 - idx {
+                return Err(error(format!(
+                    "crate `{}` is not resting on another crate",
+                    cell
+                )));
+            }
+            stack_crates.push(label);
+        }
+    }
+    Ok(crates)
+}
+
+/// A move that can't be carried out on the current stacks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    NoSuchStack(usize),
+    NotEnoughCrates {
+        stack: usize,
+        needed: usize,
+        available: usize,
+    },
+}
+impl Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchStack(stack) => write!(f, "there is no stack {}", stack),
+            Self::NotEnoughCrates {
+                stack,
+                needed,
+                available,
+            } => write!(
+                f,
+                "cannot move {} crates from stack {}, which only has {}",
+                needed, stack, available
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Instruction {
+    num: usize,
+    from: usize,
+    to: usize,
+}
+impl Instruction {
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let mut words = input.split_whitespace();
+        expect_word(input, &mut words, "move")?;
+        let num = parse_token(input, next_token(input, &mut words, "crate count")?)?;
+        expect_word(input, &mut words, "from")?;
+        let from = parse_token(input, next_token(input, &mut words, "source stack")?)?;
+        expect_word(input, &mut words, "to")?;
+        let to = parse_token(input, next_token(input, &mut words, "destination stack")?)?;
+        Ok(Self { num, from, to })
+    }
+
+    /// Make sure both stacks exist and the source has enough crates
+    fn check(&self, crates: &[Vec<char>]) -> Result<(), MoveError> {
+        for stack in [self.from, self.to] {
+            if stack == 0 || stack > crates.len() {
+                return Err(MoveError::NoSuchStack(stack));
+            }
+        }
+        let available = crates[self.from - 1].len();
+        if available < self.num {
+            return Err(MoveError::NotEnoughCrates {
+                stack: self.from,
+                needed: self.num,
+                available,
+            });
+        }
+        Ok(())
+    }
+
+    fn apply(&self, crates: &mut [Vec<char>]) -> Result<(), MoveError> {
+        self.check(crates)?;
+        for _ in 0..self.num {
+            // Grab the last crate from that crate
+            let val = crates[self.from - 1].pop().unwrap();
+            crates[self.to - 1].push(val);
+        }
+        Ok(())
+    }
+
+    fn apply_sequential(&self, crates: &mut [Vec<char>]) -> Result<(), MoveError> {
+        self.check(crates)?;
+        let from = &mut crates[self.from - 1];
+        let v = from.split_off(from.len() - self.num);
+        crates[self.to - 1].extend(v);
+        Ok(())
+    }
+}
+
+/// Consume the next word, which must be `expected`
+fn expect_word<'a, I>(line: &str, words: &mut I, expected: &str) -> Result<(), ParseError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let word = next_token(line, words, &format!("`{}`", expected))?;
+    if word == expected {
+        Ok(())
+    } else {
+        Err(ParseError::at_token(
+            line,
+            word,
+            format!("expected `{}` but found `{}`", expected, word),
+        ))
+    }
+}
+
+/// Map a line to a VectorType
+fn map_one(input: &str) -> Result<VectorType, ParseError> {
+    Instruction::new(input)
+}
+
+/// Crate on top of each stack, or a space for an empty stack
+fn top_crates(crates: &[Vec<char>]) -> ReturnType {
+    crates
+        .iter()
+        .map(|c| c.last().copied().unwrap_or(' '))
+        .collect()
+}
+
+fn part_one_internal(input: Vec<VectorType>, mut crates: Crates) -> Result<ReturnType, MoveError> {
+    for instruction in input.iter() {
+        instruction.apply(&mut crates)?;
+    }
+    Ok(top_crates(&crates))
+}
+
+fn part_two_internal(input: Vec<VectorType>, mut crates: Crates) -> Result<ReturnType, MoveError> {
+    for instruction in input.iter() {
+        instruction.apply_sequential(&mut crates)?;
+    }
+    Ok(top_crates(&crates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> &'static str {
+        "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2"
+    }
+
+    #[test]
+    fn test_one() {
+        let (crates, instructions) = DayFive::parse(input().as_bytes()).unwrap();
+        assert_eq!(crates, vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]);
+        assert_eq!(
+            part_one_internal(instructions, crates).unwrap(),
+            vec!['C', 'M', 'Z']
+        );
+    }
+
+    #[test]
+    fn test_two() {
+        let (crates, instructions) = DayFive::parse(input().as_bytes()).unwrap();
+        assert_eq!(
+            part_two_internal(instructions, crates).unwrap(),
+            vec!['M', 'C', 'D']
+        );
+    }
+
+    #[test]
+    fn test_empty_stack() {
+        let input = input().replace("move 1 from 1 to 2", "move 4 from 1 to 2");
+        let err = DayFive::parse(input.as_bytes()).unwrap_err();
+        assert_eq!(err.line, 9);
+        assert_eq!(
+            err.message,
+            "cannot move 4 crates from stack 1, which only has 2"
+        );
+    }
+}
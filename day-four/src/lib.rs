@@ -0,0 +1,109 @@
+//! Solutions for part one and part two
+use std::{collections::HashSet, io::BufRead};
+
+use aoc_utils::{next_token, parse_token, try_parse_lines, ParseError, Solution};
+
+type ReturnType = u64;
+type VectorType = Assignment;
+
+/// Solution for day four
+pub struct DayFour;
+impl Solution for DayFour {
+    type Input = Vec<VectorType>;
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        try_parse_lines(reader, map_one)
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        Ok(part_one_internal(input))
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        Ok(part_two_internal(input))
+    }
+}
+
+pub struct Assignment {
+    elf0: HashSet<u64>,
+    elf1: HashSet<u64>,
+}
+impl Assignment {
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let mut ranges = input.split(',');
+        let mut one = next_token(input, &mut ranges, "first range")?.split('-');
+        let start0: u64 = parse_token(input, next_token(input, &mut one, "range start")?)?;
+        let end0: u64 = parse_token(input, next_token(input, &mut one, "range end")?)?;
+        let mut two = next_token(input, &mut ranges, "second range")?.split('-');
+        let start1: u64 = parse_token(input, next_token(input, &mut two, "range start")?)?;
+        let end1: u64 = parse_token(input, next_token(input, &mut two, "range end")?)?;
+
+        let mut elf0 = HashSet::new();
+        (start0..=end0).for_each(|val| {
+            elf0.insert(val);
+        });
+        let mut elf1 = HashSet::new();
+        (start1..=end1).for_each(|val| {
+            elf1.insert(val);
+        });
+
+        Ok(Self { elf0, elf1 })
+    }
+
+    fn is_subset(&self) -> bool {
+        self.elf0.is_subset(&self.elf1) || self.elf1.is_subset(&self.elf0)
+    }
+
+    fn overlap(&self) -> bool {
+        !self.elf0.is_disjoint(&self.elf1) && !self.elf1.is_disjoint(&self.elf0)
+    }
+}
+
+/// Map a line to a VectorType
+fn map_one(input: &str) -> Result<VectorType, ParseError> {
+    Assignment::new(input)
+}
+
+fn part_one_internal(input: Vec<VectorType>) -> ReturnType {
+    input
+        .iter()
+        .map(|assignment| assignment.is_subset() as ReturnType)
+        .sum()
+}
+
+fn part_two_internal(input: Vec<VectorType>) -> ReturnType {
+    input
+        .iter()
+        .map(|assignment| assignment.overlap() as ReturnType)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input<'a>() -> &'a str {
+        "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8"
+    }
+
+    #[test]
+    fn test_one() {
+        let input = DayFour::parse(input().as_bytes()).unwrap();
+        assert_eq!(part_one_internal(input), 2);
+    }
+
+    #[test]
+    fn test_two() {
+        let input = DayFour::parse(input().as_bytes()).unwrap();
+        assert_eq!(part_two_internal(input), 4);
+        let err = DayFour::parse("2-4,6-8\n2-3".as_bytes()).err().unwrap();
+        assert_eq!(err.line, 2);
+    }
+}
@@ -0,0 +1,309 @@
+//! Solutions for part one and part two
+use std::{collections::HashSet, io::BufRead};
+
+use aoc_utils::{next_token, parse_token, try_parse_lines, ParseError, Solution};
+
+pub mod render;
+
+type ReturnType = usize;
+type VectorType = Command;
+
+/// Solution for day nine
+pub struct DayNine;
+impl Solution for DayNine {
+    type Input = Vec<VectorType>;
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        try_parse_lines(reader, map_one)
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        Ok(part_one_internal(input))
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        Ok(part_two_internal(input))
+    }
+}
+
+/// A point on an `N` dimensional grid, by default the plane of the puzzle
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Position<const N: usize = 2>(pub [i64; N]);
+impl<const N: usize> Default for Position<N> {
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+impl Position {
+    pub fn new(row: i64, col: i64) -> Self {
+        Self([row, col])
+    }
+
+    pub fn row(&self) -> i64 {
+        self.0[0]
+    }
+
+    pub fn col(&self) -> i64 {
+        self.0[1]
+    }
+}
+impl<const N: usize> Position<N> {
+    fn compute_tail_adjustment(&self, tail: &mut Self) {
+        let touching = (0..N).all(|axis| (self.0[axis] - tail.0[axis]).abs() <= 1);
+        if !touching {
+            // Straight along an axis or diagonally, the tail moves one step on every axis it is
+            // behind on
+            for axis in 0..N {
+                tail.0[axis] += (self.0[axis] - tail.0[axis]).signum();
+            }
+        }
+    }
+}
+
+/// One step along an axis
+///
+/// The plane has `U`/`D` on axis 0 (rows) and `L`/`R` on axis 1 (columns), the third dimension
+/// adds `F`/`B` and the fourth `A`/`K` (ana and kata). Any axis can also be written as `+n` or
+/// `-n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Direction {
+    pub axis: usize,
+    /// 1 or -1
+    pub sign: i64,
+}
+impl Direction {
+    pub const U: Self = Self::new(0, 1);
+    pub const D: Self = Self::new(0, -1);
+    pub const L: Self = Self::new(1, -1);
+    pub const R: Self = Self::new(1, 1);
+    pub const F: Self = Self::new(2, 1);
+    pub const B: Self = Self::new(2, -1);
+    pub const A: Self = Self::new(3, 1);
+    pub const K: Self = Self::new(3, -1);
+
+    const fn new(axis: usize, sign: i64) -> Self {
+        Self { axis, sign }
+    }
+
+    fn from_token(token: &str) -> Option<Self> {
+        Some(match token {
+            "U" => Self::U,
+            "D" => Self::D,
+            "L" => Self::L,
+            "R" => Self::R,
+            "F" => Self::F,
+            "B" => Self::B,
+            "A" => Self::A,
+            "K" => Self::K,
+            _ => {
+                let sign = match token.chars().next()? {
+                    '+' => 1,
+                    '-' => -1,
+                    _ => return None,
+                };
+                Self::new(token[1..].parse().ok()?, sign)
+            }
+        })
+    }
+
+    /// Panics if the axis isn't one of the `N`
+    fn apply<const N: usize>(&self, head: &mut Position<N>) {
+        head.0[self.axis] += self.sign;
+    }
+}
+
+#[derive(Debug)]
+pub struct Command {
+    pub dir: Direction,
+    pub amount: usize,
+}
+impl Command {
+    /// Parse a command moving along one of the first `dimensions` axes
+    fn from_input(input: &str, dimensions: usize) -> Result<Self, ParseError> {
+        let mut words = input.split_whitespace();
+        let token = next_token(input, &mut words, "direction")?;
+        let dir = Direction::from_token(token).ok_or_else(|| {
+            ParseError::at_token(
+                input,
+                token,
+                format!(
+                    "expected one of L, R, U, D, F, B, A, K, +n or -n but found `{}`",
+                    token
+                ),
+            )
+        })?;
+        if dir.axis >= dimensions {
+            return Err(ParseError::at_token(
+                input,
+                token,
+                format!(
+                    "`{}` moves along axis {} but there are only {} dimensions",
+                    token, dir.axis, dimensions
+                ),
+            ));
+        }
+
+        let amount = parse_token(input, next_token(input, &mut words, "step count")?)?;
+
+        Ok(Self { dir, amount })
+    }
+}
+
+/// Parse commands for a rope in `dimensions` dimensions
+pub fn parse_commands<R: BufRead>(
+    reader: R,
+    dimensions: usize,
+) -> Result<Vec<Command>, ParseError> {
+    try_parse_lines(reader, |line| Command::from_input(line, dimensions))
+}
+
+/// A rope of knots starting at the head, remembering every cell one of the knots visits
+pub struct Rope<const N: usize = 2> {
+    knots: Vec<Position<N>>,
+    tracked: usize,
+    visited: HashSet<Position<N>>,
+}
+impl<const N: usize> Rope<N> {
+    /// All `n_knots` start on the same cell, with knot 0 the head and `tracked` the knot whose
+    /// path is recorded
+    ///
+    /// Panics if there are no knots or `tracked` isn't one of them.
+    pub fn new(n_knots: usize, tracked: usize) -> Self {
+        assert!(
+            tracked < n_knots,
+            "cannot track knot {} of a rope with {} knots",
+            tracked,
+            n_knots
+        );
+        let knots = vec![Position::default(); n_knots];
+        let visited = HashSet::from([knots[tracked]]);
+        Self {
+            knots,
+            tracked,
+            visited,
+        }
+    }
+
+    /// Move the head one cell and let the rest of the rope follow
+    ///
+    /// Panics if `dir` moves along an axis the rope doesn't have.
+    pub fn step(&mut self, dir: Direction) {
+        dir.apply(&mut self.knots[0]);
+        for idx in 0..self.knots.len() - 1 {
+            let head = self.knots[idx];
+            head.compute_tail_adjustment(&mut self.knots[idx + 1]);
+        }
+        self.visited.insert(self.knots[self.tracked]);
+    }
+
+    pub fn apply(&mut self, cmd: &Command) {
+        for _ in 0..cmd.amount {
+            self.step(cmd.dir);
+        }
+    }
+
+    pub fn knots(&self) -> &[Position<N>] {
+        &self.knots
+    }
+
+    /// Every cell the tracked knot has been on, including where it started
+    pub fn visited(&self) -> &HashSet<Position<N>> {
+        &self.visited
+    }
+}
+
+/// Number of cells knot `tracked` of an `n_knots` rope in `N` dimensions visits
+pub fn count_visited<const N: usize>(input: &[Command], n_knots: usize, tracked: usize) -> usize {
+    let mut rope = Rope::<N>::new(n_knots, tracked);
+    input.iter().for_each(|cmd| rope.apply(cmd));
+    rope.visited().len()
+}
+
+/// Map a line to a VectorType
+fn map_one(input: &str) -> Result<VectorType, ParseError> {
+    Command::from_input(input, 2)
+}
+
+fn part_one_internal(input: Vec<VectorType>) -> ReturnType {
+    count_visited::<2>(&input, 2, 1)
+}
+
+fn part_two_internal(input: Vec<VectorType>) -> ReturnType {
+    count_visited::<2>(&input, 10, 9)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input<'a>() -> &'a str {
+        "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2"
+    }
+
+    #[test]
+    fn test_one() {
+        let input = try_parse_lines(input().as_bytes(), map_one).unwrap();
+        assert_eq!(part_one_internal(input), 13);
+    }
+
+    fn larger_input<'a>() -> &'a str {
+        "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20"
+    }
+
+    #[test]
+    fn test_two() {
+        let input = try_parse_lines(input().as_bytes(), map_one).unwrap();
+        assert_eq!(part_two_internal(input), 1);
+        let input = try_parse_lines(larger_input().as_bytes(), map_one).unwrap();
+        assert_eq!(part_two_internal(input), 36);
+    }
+
+    #[test]
+    fn test_tracked_knot() {
+        let input = try_parse_lines(input().as_bytes(), map_one).unwrap();
+        // The head of the first example goes everywhere the rope does
+        assert_eq!(
+            count_visited::<2>(&input, 10, 0),
+            count_visited::<2>(&input, 1, 0)
+        );
+        assert_eq!(count_visited::<2>(&input, 10, 1), 13);
+    }
+
+    #[test]
+    fn test_dimensions() {
+        // Commands along the first two axes behave the same however many dimensions there are
+        let input = try_parse_lines(larger_input().as_bytes(), map_one).unwrap();
+        assert_eq!(count_visited::<3>(&input, 10, 9), 36);
+        assert_eq!(
+            count_visited::<4>(&input, 2, 1),
+            count_visited::<2>(&input, 2, 1)
+        );
+
+        let input = parse_commands("F 3\nU 3\n+3 2\nK 1".as_bytes(), 4).unwrap();
+        let mut rope = Rope::<4>::new(2, 1);
+        input.iter().for_each(|cmd| rope.apply(cmd));
+        // The tail catches up diagonally across the first and fourth axes
+        assert_eq!(rope.knots(), [Position([3, 0, 3, 1]); 2]);
+        assert_eq!(rope.visited().len(), 6);
+
+        let err = parse_commands("U 1\nF 2".as_bytes(), 2).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert!(parse_commands("+ 2".as_bytes(), 4).is_err());
+    }
+}
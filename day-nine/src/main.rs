@@ -1,11 +1,19 @@
 //! Command line executable for running part one and part two
 use std::{
-    collections::HashSet,
-    fs::File,
-    io::{BufRead, BufReader},
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
 };
 
+use aoc_utils::{exit_with, open_or_exit, parse_or_exit, run_part, write_ppm, Part};
 use clap::Parser;
+use day_nine::{
+    count_visited, parse_commands,
+    render::{frame_image, Animation, Viewport},
+    DayNine, Rope,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -15,189 +23,153 @@ struct Args {
     input_file: String,
 
     #[command(subcommand)]
-    part: Part,
-}
-
-#[derive(clap::Subcommand, Debug)]
-enum Part {
-    Part1,
-    Part2,
-}
-
-fn main() {
-    let args = Args::parse();
-
-    let file = BufReader::new(File::open(args.input_file).expect("Cannot find file"));
-
-    let answer = match args.part {
-        Part::Part1 => part_one(file),
-        Part::Part2 => part_two(file),
-    };
-
-    println!("{:?}", answer);
-}
-
-fn part_one(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file, map_one);
-    part_one_internal(input)
-}
-
-fn part_two(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file, map_two);
-    part_two_internal(input)
-}
-
-fn parse_input<F, T>(file: BufReader<File>, f: F) -> Vec<T>
-where
-    F: Fn(&str) -> T,
-{
-    file.lines().map(|x| f(x.unwrap().as_str())).collect()
-}
-
-// TODO -- Update this with the return type
-type ReturnType = usize;
-type VectorType = Command;
-
-#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
-struct Position {
-    row: i64,
-    col: i64,
-}
-impl Position {
-    fn compute_tail_adjustment(&self, tail: &mut Self) {
-        let row_offset = self.row - tail.row;
-        let col_offset = self.col - tail.col;
-
-        if row_offset.abs() <= 1 && col_offset.abs() <= 1 {
-            // If touching
-        } else if col_offset == 0 {
-            // If along row axis
-            tail.row += row_offset / 2;
-        } else if row_offset == 0 {
-            // If along column
-            tail.col += col_offset / 2;
-        } else {
-            // If diagonal
-            tail.row += row_offset.signum();
-            tail.col += col_offset.signum();
+    command: Command,
+}
+
+#[derive(clap::Args, Debug)]
+struct Knots {
+    /// Number of knots, including the head
+    #[arg(short, long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    knots: u64,
+
+    /// Knot to follow, counting the head as 0 [default: the tail]
+    #[arg(short, long)]
+    track: Option<usize>,
+}
+impl Knots {
+    /// The number of knots and the one to track, or exit if the tracked knot isn't on the rope
+    fn resolve(&self) -> (usize, usize) {
+        let knots = self.knots as usize;
+        let track = self.track.unwrap_or(knots - 1);
+        if track >= knots {
+            eprintln!(
+                "error: cannot track knot {} of a rope with {} knots",
+                track, knots
+            );
+            std::process::exit(1);
         }
+        (knots, track)
     }
 }
 
-enum Direction {
-    L,
-    R,
-    U,
-    D,
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    Text,
+    Ppm,
 }
-impl Direction {
-    fn apply(&self, head: &mut Position) {
-        match self {
-            Self::L => head.col -= 1,
-            Self::R => head.col += 1,
-            Self::U => head.row += 1,
-            Self::D => head.row -= 1,
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    #[command(flatten)]
+    Part(Part),
+    /// Count the cells one knot of a rope of any length visits
+    Rope {
+        #[command(flatten)]
+        knots: Knots,
+
+        /// Number of dimensions the rope moves in
+        #[arg(short, long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..=4))]
+        dimensions: u64,
+    },
+    /// Play the rope back one step at a time in the terminal, or write each step out as a frame
+    Animate {
+        #[command(flatten)]
+        knots: Knots,
+
+        /// Columns on screen
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+        width: u64,
+
+        /// Rows on screen
+        #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+        height: u64,
+
+        /// Milliseconds to wait between steps
+        #[arg(short, long, default_value_t = 50)]
+        delay: u64,
+
+        /// Write numbered frames to this directory instead of animating
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Format of the frames written to the output directory
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+
+        /// Pixels per cell in PPM frames
+        #[arg(long, default_value_t = 8)]
+        scale: usize,
+    },
+}
+
+fn write_frames(animation: Animation, dir: &Path, format: Format, scale: usize) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (idx, frame) in animation.enumerate() {
+        let extension = match format {
+            Format::Text => "txt",
+            Format::Ppm => "ppm",
+        };
+        let mut file = BufWriter::new(File::create(
+            dir.join(format!("frame_{:05}.{}", idx, extension)),
+        )?);
+        match format {
+            Format::Text => write!(file, "{}", frame)?,
+            Format::Ppm => write_ppm(file, &frame_image(&frame), scale)?,
         }
     }
+    Ok(())
 }
 
-struct Command {
-    dir: Direction,
-    amount: usize,
-}
-impl Command {
-    fn from_input(input: &str) -> Self {
-        let mut words = input.split_whitespace();
-        let dir = match words.next().unwrap() {
-            "L" => Direction::L,
-            "R" => Direction::R,
-            "U" => Direction::U,
-            "D" => Direction::D,
-            _ => panic!("This bad"),
-        };
-
-        let amount = words.next().unwrap().parse().unwrap();
-
-        Self { dir, amount }
-    }
+fn main() {
+    let args = Args::parse();
 
-    fn apply(&self, head: &mut Position, tail: &mut Position, tail_set: &mut HashSet<Position>) {
-        for _ in 0..self.amount {
-            self.dir.apply(head);
-            head.compute_tail_adjustment(tail);
-            tail_set.insert(*tail);
+    match args.command {
+        Command::Part(part) => run_part::<DayNine>(&args.input_file, part),
+        Command::Rope { knots, dimensions } => {
+            let (knots, track) = knots.resolve();
+            let filename = &args.input_file;
+            let input = parse_commands(open_or_exit(filename), dimensions as usize)
+                .unwrap_or_else(|e| exit_with(e.with_file(filename)));
+            let count = match dimensions {
+                1 => count_visited::<1>,
+                2 => count_visited::<2>,
+                3 => count_visited::<3>,
+                _ => count_visited::<4>,
+            };
+            println!("{}", count(&input, knots, track));
         }
-    }
-
-    fn apply_to_vec(&self, knots: &mut [&mut Position], tail_set: &mut HashSet<Position>) {
-        for _ in 0..self.amount {
-            self.dir.apply(knots[0]);
-            for idx in 0..knots.len() - 1 {
-                let head = *knots[idx];
-                head.compute_tail_adjustment(knots[idx + 1]);
+        Command::Animate {
+            knots,
+            width,
+            height,
+            delay,
+            output,
+            format,
+            scale,
+        } => {
+            let (knots, track) = knots.resolve();
+            let input = parse_or_exit::<DayNine>(&args.input_file);
+            let rope = Rope::new(knots, track);
+            let animation = Animation::new(
+                &input,
+                rope,
+                Viewport::centered(width as usize, height as usize),
+            );
+            match output {
+                Some(dir) => {
+                    if let Err(err) = write_frames(animation, &dir, format, scale) {
+                        eprintln!("error: cannot write frames to {}: {}", dir.display(), err);
+                        std::process::exit(1);
+                    }
+                }
+                None => {
+                    for (step, frame) in animation.enumerate() {
+                        // Clear the screen and go back to the top left before each frame
+                        println!("\x1b[2J\x1b[H{}step {}", frame, step);
+                        thread::sleep(Duration::from_millis(delay));
+                    }
+                }
             }
-            tail_set.insert(*knots[knots.len() - 1]);
         }
     }
 }
-
-/// Map a line to a VectorType
-fn map_one(input: &str) -> VectorType {
-    Command::from_input(input)
-}
-
-/// Map a line to a VectorType
-fn map_two(input: &str) -> VectorType {
-    Command::from_input(input)
-}
-
-// TODO Implement this
-fn part_one_internal(input: Vec<VectorType>) -> ReturnType {
-    let mut head = Position::default();
-    let mut tail = Position::default();
-    let mut tail_set = HashSet::new();
-    input
-        .iter()
-        .for_each(|cmd| cmd.apply(&mut head, &mut tail, &mut tail_set));
-    tail_set.len()
-}
-
-// TODO Implement this
-fn part_two_internal(input: Vec<VectorType>) -> ReturnType {
-    let mut knots_objs = vec![Position::default(); 10];
-    let mut knots: Vec<&mut Position> = knots_objs.iter_mut().collect();
-
-    let mut tail_set = HashSet::new();
-    input
-        .iter()
-        .for_each(|cmd| cmd.apply_to_vec(&mut knots, &mut tail_set));
-    tail_set.len()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    fn input<'a>() -> &'a str {
-        "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2"
-    }
-
-    #[test]
-    fn test_one() {
-        let input: Vec<VectorType> = input()
-            .lines()
-            .map(|line| Command::from_input(line))
-            .collect();
-        assert_eq!(part_one_internal(input), 13);
-    }
-
-    #[test]
-    fn test_two() {}
-}
@@ -0,0 +1,178 @@
+//! Drawing the rope one step at a time, like the pictures in the puzzle.
+//!
+//! The head is `H`, the other knots are numbered (or `T` for a two knot rope), `s` is the start
+//! and `#` marks every cell the tracked knot has visited.
+use aoc_utils::{Grid, Rgb};
+
+use crate::{Command, Position, Rope};
+
+/// The part of the plane on screen, scrolled to keep the head in view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    /// Row shown on the first line, rows increase going up
+    pub top: i64,
+    /// Column shown in the first character
+    pub left: i64,
+    pub width: usize,
+    pub height: usize,
+}
+impl Viewport {
+    /// A viewport with the start in the middle
+    ///
+    /// Panics if `width` or `height` is zero, as there would be nowhere to keep the head.
+    pub fn centered(width: usize, height: usize) -> Self {
+        assert!(
+            width > 0 && height > 0,
+            "a viewport needs at least one cell"
+        );
+        Self {
+            top: height as i64 / 2,
+            left: -(width as i64 / 2),
+            width,
+            height,
+        }
+    }
+
+    /// Scroll so `pos` stays a quarter of the way in from every edge
+    pub fn follow(&mut self, pos: Position) {
+        let (width, height) = (self.width as i64, self.height as i64);
+        let margin = width.min(height) / 4;
+        self.left = self
+            .left
+            .clamp(pos.col() - width + 1 + margin, pos.col() - margin);
+        self.top = self
+            .top
+            .clamp(pos.row() + margin, pos.row() + height - 1 - margin);
+    }
+
+    /// Screen cell showing `pos`, if it is in view
+    fn cell(&self, pos: Position) -> Option<(usize, usize)> {
+        let row = usize::try_from(self.top - pos.row()).ok()?;
+        let col = usize::try_from(pos.col() - self.left).ok()?;
+        (row < self.height && col < self.width).then_some((row, col))
+    }
+}
+
+/// Character for knot `idx` of a rope of `n_knots`
+fn knot_label(idx: usize, n_knots: usize) -> char {
+    match idx {
+        0 => 'H',
+        1 if n_knots == 2 => 'T',
+        idx => char::from_digit(idx as u32, 10).unwrap_or('+'),
+    }
+}
+
+/// Draw the part of the rope and its trail inside `viewport`
+pub fn render_frame(rope: &Rope, viewport: &Viewport) -> Grid<char> {
+    let mut frame = Grid::filled(viewport.height, viewport.width, '.');
+    for pos in rope.visited() {
+        if let Some(cell) = viewport.cell(*pos) {
+            frame[cell] = '#';
+        }
+    }
+    if let Some(cell) = viewport.cell(Position::default()) {
+        frame[cell] = 's';
+    }
+    // Draw from the tail up so knots nearer the head end up on top
+    let knots = rope.knots();
+    for (idx, pos) in knots.iter().enumerate().rev() {
+        if let Some(cell) = viewport.cell(*pos) {
+            frame[cell] = knot_label(idx, knots.len());
+        }
+    }
+    frame
+}
+
+/// Colour each character of a frame for `write_ppm`
+pub fn frame_image(frame: &Grid<char>) -> Grid<Rgb> {
+    frame.map(|c| match c {
+        '.' => [20, 20, 30],
+        '#' => [90, 90, 110],
+        's' => [60, 160, 60],
+        'H' => [230, 40, 40],
+        _ => [240, 200, 60],
+    })
+}
+
+/// Every frame of the simulation, starting before the first step and then after each step
+pub struct Animation<'a> {
+    commands: &'a [Command],
+    /// Index of the command being carried out and how many of its steps are done
+    command: usize,
+    steps_done: usize,
+    rope: Rope,
+    viewport: Viewport,
+    started: bool,
+}
+impl<'a> Animation<'a> {
+    pub fn new(commands: &'a [Command], rope: Rope, viewport: Viewport) -> Self {
+        Self {
+            commands,
+            command: 0,
+            steps_done: 0,
+            rope,
+            viewport,
+            started: false,
+        }
+    }
+}
+impl<'a> Iterator for Animation<'a> {
+    type Item = Grid<char>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.started {
+            // Skip over any commands that move zero steps
+            while self.steps_done >= self.commands.get(self.command)?.amount {
+                self.command += 1;
+                self.steps_done = 0;
+            }
+            self.rope.step(self.commands[self.command].dir);
+            self.steps_done += 1;
+            self.viewport.follow(self.rope.knots()[0]);
+        }
+        self.started = true;
+        Some(render_frame(&self.rope, &self.viewport))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DayNine;
+    use aoc_utils::Solution;
+
+    #[test]
+    fn test_frames() {
+        let commands = DayNine::parse("R 4\nU 4".as_bytes()).unwrap();
+        // Big enough that the head never gets near enough the edge to scroll
+        let viewport = Viewport {
+            top: 5,
+            left: -1,
+            width: 8,
+            height: 7,
+        };
+        let frames: Vec<String> = Animation::new(&commands, Rope::new(2, 1), viewport)
+            .map(|frame| frame.to_string())
+            .collect();
+        assert_eq!(frames.len(), 9);
+        let blank = "........\n";
+        assert_eq!(frames[0], format!("{0}{0}{0}{0}{0}.H......\n{0}", blank));
+        assert_eq!(frames[4], format!("{0}{0}{0}{0}{0}.s##TH..\n{0}", blank));
+        assert_eq!(
+            frames[8],
+            format!(
+                "{0}.....H..\n.....T..\n.....#..\n.....#..\n.s###...\n{0}",
+                blank
+            )
+        );
+    }
+
+    #[test]
+    fn test_follow() {
+        let mut viewport = Viewport::centered(8, 8);
+        viewport.follow(Position::new(0, 10));
+        assert_eq!(viewport.cell(Position::new(0, 10)), Some((4, 5)));
+        viewport.follow(Position::new(-10, 10));
+        assert_eq!(viewport.cell(Position::new(-10, 10)), Some((5, 5)));
+    }
+}
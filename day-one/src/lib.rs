@@ -0,0 +1,103 @@
+//! Solutions for part one and part two
+use std::io::BufRead;
+
+use aoc_utils::{parse_token, try_read_lines, ParseError, Solution};
+
+type ReturnType = u64;
+type VectorType = Vec<u64>;
+
+/// Solution for day one
+pub struct DayOne;
+impl Solution for DayOne {
+    type Input = Vec<VectorType>;
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        parse_input(reader)
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        Ok(part_one_internal(input))
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        Ok(part_two_internal(input))
+    }
+}
+
+fn parse_input<R: BufRead>(reader: R) -> Result<Vec<VectorType>, ParseError> {
+    let input = try_read_lines(reader)?;
+    let mut elves: Vec<VectorType> = Vec::new();
+    let mut elf: VectorType = Vec::new();
+    for (idx, line) in input.iter().enumerate() {
+        if line.is_empty() {
+            elves.push(elf);
+            elf = Vec::new();
+        } else {
+            elf.push(parse_token(line, line).map_err(|e| e.with_line(idx + 1))?);
+        }
+    }
+    // The last elf needn't be followed by a blank line
+    if !elf.is_empty() {
+        elves.push(elf);
+    }
+    if elves.is_empty() {
+        return Err(ParseError::input(
+            "expected the calories carried by at least one elf",
+        ));
+    }
+    Ok(elves)
+}
+
+fn part_one_internal(input: Vec<VectorType>) -> ReturnType {
+    input
+        .into_iter()
+        .map(|elf| elf.into_iter().sum())
+        .reduce(|greatest, val| if val > greatest { val } else { greatest })
+        .expect("parsing checks there is at least one elf")
+}
+
+fn part_two_internal(input: Vec<VectorType>) -> ReturnType {
+    let mut calories: VectorType = input.into_iter().map(|elf| elf.into_iter().sum()).collect();
+    calories.sort();
+    calories.into_iter().rev().take(3).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input<'a>() -> &'a str {
+        "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000"
+    }
+
+    #[test]
+    fn test_one() {
+        let input = DayOne::parse(input().as_bytes()).unwrap();
+        assert_eq!(input.len(), 5);
+        assert_eq!(part_one_internal(input), 24000);
+    }
+
+    #[test]
+    fn test_two() {
+        let input = DayOne::parse(input().as_bytes()).unwrap();
+        assert_eq!(part_two_internal(input), 45000);
+        let err = DayOne::parse("1000\n\nlots".as_bytes()).unwrap_err();
+        assert_eq!((err.line, err.token.as_str()), (3, "lots"));
+        assert!(DayOne::parse("".as_bytes()).is_err());
+    }
+}
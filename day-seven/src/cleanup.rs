@@ -0,0 +1,323 @@
+//! Choosing directories to delete to make room for the update.
+use crate::filesystem::{EntryKind, FileSystem};
+
+/// Size of the disk and the free space the update needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capacity {
+    pub total: usize,
+    pub needed: usize,
+}
+impl Default for Capacity {
+    fn default() -> Self {
+        Self {
+            total: 70000000,
+            needed: 30000000,
+        }
+    }
+}
+impl Capacity {
+    /// Whether the update fits on the disk at all, in which case deleting `/` is always enough
+    pub fn fits(&self) -> bool {
+        self.needed <= self.total
+    }
+
+    /// Space that still has to be freed, which is zero if there is already room
+    pub fn to_free(&self, fs: &FileSystem) -> usize {
+        let free = self.total.saturating_sub(fs.used_space());
+        self.needed.saturating_sub(free)
+    }
+}
+
+/// Directories to delete and the space that frees
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub paths: Vec<String>,
+    pub reclaimed: usize,
+}
+
+/// A directory in pre-order, as the planner sees it
+struct Candidate {
+    path: String,
+    size: usize,
+    /// Index of the first directory after this one's subtree
+    next: usize,
+}
+
+fn candidates(fs: &FileSystem) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut depths = Vec::new();
+    for entry in fs.walk().filter(|entry| entry.kind == EntryKind::Directory) {
+        depths.push(entry.depth);
+        candidates.push(Candidate {
+            path: entry.path,
+            size: entry.size,
+            next: 0,
+        });
+    }
+    // A subtree ends at the next directory that is no deeper
+    for idx in 0..candidates.len() {
+        candidates[idx].next = (idx + 1..candidates.len())
+            .find(|&other| depths[other] <= depths[idx])
+            .unwrap_or(candidates.len());
+    }
+    candidates
+}
+
+/// The single smallest directory that frees enough space
+///
+/// This is only `None` when the update doesn't fit on the disk.
+pub fn smallest_directory(fs: &FileSystem, capacity: &Capacity) -> Option<Plan> {
+    let to_free = capacity.to_free(fs);
+    if to_free == 0 {
+        return Some(Plan {
+            paths: Vec::new(),
+            reclaimed: 0,
+        });
+    }
+    fs.find(|entry| entry.is_dir())
+        .filter(|entry| entry.size >= to_free)
+        .min_by_key(|entry| entry.size)
+        .map(|entry| Plan {
+            paths: vec![entry.path],
+            reclaimed: entry.size,
+        })
+}
+
+/// The directories, none inside another, that free enough space while deleting the least
+///
+/// This is a knapsack over the tree: each directory is either deleted whole or kept with its
+/// subdirectories still up for deletion. Going through the directories in pre-order, taking one
+/// skips the rest of its subtree, so the amounts each suffix of that order can free are built
+/// from the back, capped at the best single directory. Each suffix can free everything the next
+/// one can and more, so only the sets later steps still need are kept, the ones at the ends of
+/// the current directory's ancestors, along with the amounts each step adds to find the way back.
+///
+/// Those amounts are stored as `u32`, so past 4 GiB to free this settles for the single best
+/// directory, which would need half a gigabyte for every set anyway.
+pub fn best_combination(fs: &FileSystem, capacity: &Capacity) -> Option<Plan> {
+    let single = smallest_directory(fs, capacity)?;
+    let to_free = capacity.to_free(fs);
+    if to_free == 0 || u32::try_from(single.reclaimed).is_err() {
+        return Some(single);
+    }
+
+    let candidates = candidates(fs);
+    let n = candidates.len();
+    // The last step to read each set, as they are only read by earlier indices
+    let mut last_use: Vec<usize> = (0..=n).map(|idx| idx.saturating_sub(1)).collect();
+    for (idx, candidate) in candidates.iter().enumerate() {
+        last_use[candidate.next] = last_use[candidate.next].min(idx);
+    }
+
+    // Amounts that deleting directories from each index on can free exactly
+    let mut freeable: Vec<Option<BitSet>> = vec![None; n + 1];
+    let mut last = BitSet::new(single.reclaimed + 1);
+    last.insert(0);
+    freeable[n] = Some(last);
+    // What each index adds to the amounts the next one can free, in order
+    let mut added: Vec<Vec<u32>> = vec![Vec::new(); n];
+    for idx in (0..n).rev() {
+        let candidate = &candidates[idx];
+        // Keep this directory
+        let mut current = freeable[idx + 1].clone().unwrap();
+        // Or delete it, skipping everything inside
+        let deleted = freeable[candidate.next].as_ref().unwrap();
+        current.union_shifted(deleted, candidate.size, |size| added[idx].push(size as u32));
+        for done in [idx + 1, candidate.next] {
+            if last_use[done] == idx {
+                freeable[done] = None;
+            }
+        }
+        freeable[idx] = Some(current);
+    }
+
+    let all = freeable[0].as_ref().unwrap();
+    let reclaimed = (to_free..=single.reclaimed).find(|&size| all.contains(size))?;
+
+    // Walk forwards, following whichever choice can still make up the rest
+    let mut paths = Vec::new();
+    let (mut idx, mut left) = (0, reclaimed);
+    while left > 0 {
+        if added[idx].binary_search(&(left as u32)).is_err() {
+            idx += 1;
+        } else {
+            let candidate = &candidates[idx];
+            paths.push(candidate.path.clone());
+            left -= candidate.size;
+            idx = candidate.next;
+        }
+    }
+    Some(Plan { paths, reclaimed })
+}
+
+/// A set of the numbers below a fixed bound
+#[derive(Clone)]
+struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+impl BitSet {
+    fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    fn contains(&self, value: usize) -> bool {
+        value < self.len && self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    fn insert(&mut self, value: usize) {
+        if value < self.len {
+            self.words[value / 64] |= 1 << (value % 64);
+        }
+    }
+
+    /// Add `shift` to every number in `other` and add those that fit to this set, calling
+    /// `added` with each one that wasn't already in it, smallest first
+    fn union_shifted<F: FnMut(usize)>(&mut self, other: &BitSet, shift: usize, mut added: F) {
+        let (word_shift, bit_shift) = (shift / 64, shift % 64);
+        let mut or_word = |target: usize, bits: u64| {
+            if target >= self.words.len() {
+                return;
+            }
+            // Nothing past the end
+            let mut bits = bits;
+            if target == self.words.len() - 1 && !self.len.is_multiple_of(64) {
+                bits &= (1 << (self.len % 64)) - 1;
+            }
+            let mut new = bits & !self.words[target];
+            self.words[target] |= new;
+            while new != 0 {
+                added(target * 64 + new.trailing_zeros() as usize);
+                new &= new - 1;
+            }
+        };
+        for (idx, word) in other.words.iter().enumerate() {
+            let target = idx + word_shift;
+            if bit_shift == 0 {
+                or_word(target, *word);
+            } else {
+                // The low part of this word, then the high part of the one before
+                let carried = idx
+                    .checked_sub(1)
+                    .map_or(0, |prev| other.words[prev] >> (64 - bit_shift));
+                or_word(target, (word << bit_shift) | carried);
+            }
+        }
+        if bit_shift > 0 {
+            if let Some(&top) = other.words.last() {
+                or_word(other.words.len() + word_shift, top >> (64 - bit_shift));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DaySeven;
+    use aoc_utils::Solution;
+
+    #[test]
+    fn test_plans() {
+        let fs = DaySeven::parse(
+            "$ cd /
+$ ls
+dir a
+dir b
+dir c
+$ cd a
+$ ls
+400 x
+dir d
+$ cd d
+$ ls
+300 y
+$ cd /b
+$ ls
+350 z
+$ cd /c
+$ ls
+900 w"
+                .as_bytes(),
+        )
+        .unwrap();
+        let capacity = Capacity {
+            total: 2000,
+            needed: 700,
+        };
+        assert_eq!(capacity.to_free(&fs), 650);
+
+        let single = smallest_directory(&fs, &capacity).unwrap();
+        assert_eq!(
+            (single.paths, single.reclaimed),
+            (vec!["/a".to_string()], 700)
+        );
+
+        let combined = best_combination(&fs, &capacity).unwrap();
+        assert_eq!(combined.reclaimed, 650);
+        assert_eq!(combined.paths, vec!["/a/d", "/b"]);
+
+        let roomy = Capacity {
+            total: 10000,
+            needed: 1000,
+        };
+        assert_eq!(best_combination(&fs, &roomy).unwrap().paths.len(), 0);
+
+        let too_big = Capacity {
+            total: 2000,
+            needed: 2100,
+        };
+        assert!(!too_big.fits());
+        assert_eq!(smallest_directory(&fs, &too_big), None);
+        assert_eq!(best_combination(&fs, &too_big), None);
+        // Even a disk already overfull has room once everything is gone
+        let overfull = Capacity {
+            total: 1000,
+            needed: 1000,
+        };
+        assert_eq!(smallest_directory(&fs, &overfull).unwrap().paths, vec!["/"]);
+
+        // Sizes in the millions cost no more than small ones
+        let big = Capacity {
+            total: 1_100_000_000,
+            needed: 700_000_000,
+        };
+        let scaled = DaySeven::parse(
+            "$ cd /
+$ ls
+dir a
+dir b
+$ cd a
+$ ls
+400000000 x
+dir d
+$ cd d
+$ ls
+300000000 y
+$ cd /b
+$ ls
+350000000 z"
+                .as_bytes(),
+        )
+        .unwrap();
+        let combined = best_combination(&scaled, &big).unwrap();
+        assert_eq!(combined.reclaimed, 650_000_000);
+        assert_eq!(combined.paths, vec!["/a/d", "/b"]);
+
+        let mut set = BitSet::new(70);
+        set.insert(0);
+        let mut other = BitSet::new(70);
+        for value in [0, 3, 61] {
+            other.insert(value);
+        }
+        let mut added = Vec::new();
+        set.union_shifted(&other, 5, |value| added.push(value));
+        assert_eq!(added, vec![5, 8, 66]);
+        set.union_shifted(&other, 6, |value| added.push(value));
+        assert_eq!(added, vec![5, 8, 66, 6, 9, 67]);
+        assert!(set.contains(67) && !set.contains(61));
+    }
+}
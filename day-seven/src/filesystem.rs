@@ -0,0 +1,611 @@
+//! An in-memory filesystem rebuilt by replaying the terminal output.
+//!
+//! Listing the same directory twice doesn't count its files twice, and `cd` into a directory
+//! that was never listed creates it, just like `mkdir -p`.
+use std::fmt::{self, Display};
+
+use id_tree::{InsertBehavior, Node, NodeId, Tree};
+
+use crate::{Command, TerminalLine};
+
+#[derive(Debug, Clone)]
+pub struct LocalFile {
+    size: usize,
+    name: String,
+}
+impl LocalFile {
+    pub fn new(name: &str, size: usize) -> Self {
+        Self {
+            size,
+            name: name.to_string(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Directory {
+    name: String,
+    files: Vec<LocalFile>,
+    pub directory_data_size: usize,
+}
+impl Directory {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            files: Vec::new(),
+            directory_data_size: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn files(&self) -> &[LocalFile] {
+        &self.files
+    }
+
+    /// Add a file, replacing any earlier listing of it
+    fn add_file(&mut self, file: LocalFile) {
+        match self.files.iter_mut().find(|x| x.name == file.name) {
+            Some(existing) => *existing = file,
+            None => self.files.push(file),
+        }
+    }
+
+    fn file(&self, name: &str) -> Option<&LocalFile> {
+        self.files.iter().find(|x| x.name == name)
+    }
+
+    fn get_file_sizes(&self) -> usize {
+        self.files.iter().map(|x| x.size).sum()
+    }
+
+    fn add_sizes_from_below(&mut self, size: usize) {
+        self.directory_data_size += size;
+    }
+
+    /// Size of everything in and below this directory, once the filesystem is finished
+    pub fn get_full_size(&self) -> usize {
+        self.directory_data_size + self.get_file_sizes()
+    }
+}
+
+/// Terminal output that contradicts what was seen before
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// A directory was expected but there is a file with that name
+    NotADirectory(String),
+    /// A file was listed but there is a directory with that name
+    IsADirectory(String),
+}
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotADirectory(path) => write!(f, "`{}` is a file, not a directory", path),
+            Self::IsADirectory(path) => write!(f, "`{}` is a directory, not a file", path),
+        }
+    }
+}
+
+/// Replays terminal lines one at a time, tracking the working directory
+#[derive(Debug)]
+pub struct Shell {
+    tree: Tree<Directory>,
+    cwd: NodeId,
+}
+impl Default for Shell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Shell {
+    /// Start in an empty root directory
+    pub fn new() -> Self {
+        let mut tree = Tree::new();
+        let cwd = tree
+            .insert(Node::new(Directory::new("/")), InsertBehavior::AsRoot)
+            .unwrap();
+        Self { tree, cwd }
+    }
+
+    pub fn run(&mut self, line: TerminalLine) -> Result<(), ReplayError> {
+        match line {
+            TerminalLine::Command(Command::Cd(path)) => {
+                self.cwd = self.resolve(&path)?;
+            }
+            TerminalLine::Command(Command::Ls) => {}
+            TerminalLine::Directory(dir) => {
+                let cwd = self.cwd.clone();
+                self.child_dir(&cwd, dir.name())?;
+            }
+            TerminalLine::File(file) => {
+                if self.find_child(&self.cwd, file.name()).is_some() {
+                    return Err(ReplayError::IsADirectory(self.join(&self.cwd, file.name())));
+                }
+                self.tree
+                    .get_mut(&self.cwd)
+                    .unwrap()
+                    .data_mut()
+                    .add_file(file);
+            }
+        }
+        Ok(())
+    }
+
+    /// Work out the totals of every directory
+    pub fn finish(mut self) -> FileSystem {
+        populate_tree(&mut self.tree);
+        FileSystem { tree: self.tree }
+    }
+
+    /// Follow an absolute or relative path from the working directory, creating directories
+    fn resolve(&mut self, path: &str) -> Result<NodeId, ReplayError> {
+        let mut node_id = if path.starts_with('/') {
+            self.tree.root_node_id().unwrap().clone()
+        } else {
+            self.cwd.clone()
+        };
+        for component in path.split('/') {
+            node_id = match component {
+                "" | "." => node_id,
+                // The root is its own parent
+                ".." => self
+                    .tree
+                    .get(&node_id)
+                    .unwrap()
+                    .parent()
+                    .cloned()
+                    .unwrap_or(node_id),
+                name => self.child_dir(&node_id, name)?,
+            };
+        }
+        Ok(node_id)
+    }
+
+    /// The subdirectory `name` of `parent`, creating it if it hasn't been seen yet
+    fn child_dir(&mut self, parent: &NodeId, name: &str) -> Result<NodeId, ReplayError> {
+        if let Some(child) = self.find_child(parent, name) {
+            return Ok(child);
+        }
+        if self.tree.get(parent).unwrap().data().file(name).is_some() {
+            return Err(ReplayError::NotADirectory(self.join(parent, name)));
+        }
+        Ok(self
+            .tree
+            .insert(
+                Node::new(Directory::new(name)),
+                InsertBehavior::UnderNode(parent),
+            )
+            .unwrap())
+    }
+
+    fn find_child(&self, parent: &NodeId, name: &str) -> Option<NodeId> {
+        find_child(&self.tree, parent, name)
+    }
+
+    fn join(&self, parent: &NodeId, name: &str) -> String {
+        join(&path_of(&self.tree, parent), name)
+    }
+}
+
+fn find_child(tree: &Tree<Directory>, parent: &NodeId, name: &str) -> Option<NodeId> {
+    tree.get(parent)
+        .unwrap()
+        .children()
+        .iter()
+        .find(|child| tree.get(child).unwrap().data().name == name)
+        .cloned()
+}
+
+/// Absolute path of a directory
+fn path_of(tree: &Tree<Directory>, node_id: &NodeId) -> String {
+    let mut names: Vec<&str> = tree
+        .ancestors(node_id)
+        .unwrap()
+        .map(|node| node.data().name())
+        .collect();
+    names.reverse();
+    names.push(tree.get(node_id).unwrap().data().name());
+    // Skip the root, which is named `/`
+    match &names[1..] {
+        [] => "/".to_string(),
+        names => names.iter().map(|name| format!("/{}", name)).collect(),
+    }
+}
+
+fn join(parent: &str, name: &str) -> String {
+    format!("{}/{}", parent.trim_end_matches('/'), name)
+}
+
+fn populate_tree(tree: &mut Tree<Directory>) {
+    let mut node_ids: Vec<NodeId> = tree
+        .traverse_level_order_ids(tree.root_node_id().unwrap())
+        .unwrap()
+        .collect();
+    // Now, make bottom to top
+    node_ids.reverse();
+    // Traverse and add sizes to the trees
+    for node_id in node_ids {
+        // Get Node
+        let node = tree.get(&node_id).unwrap();
+        let dir = node.data();
+        // Compute full size of this bad boy
+        let size = dir.get_file_sizes() + dir.directory_data_size;
+        // Add this to the parent
+        let parent_id = node.parent().cloned();
+        if let Some(parent_id) = parent_id {
+            tree.get_mut(&parent_id)
+                .unwrap()
+                .data_mut()
+                .add_sizes_from_below(size);
+        }
+    }
+}
+
+/// Whether an entry is a file or a directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Directory,
+}
+
+/// A file or directory visited by `FileSystem::walk`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub path: String,
+    /// Size of a file, or total size of a directory
+    pub size: usize,
+    pub kind: EntryKind,
+    /// Number of directories above this entry, so the root is at depth 0
+    pub depth: usize,
+}
+impl Entry {
+    pub fn is_dir(&self) -> bool {
+        self.kind == EntryKind::Directory
+    }
+
+    /// Last component of the path
+    pub fn name(&self) -> &str {
+        match self.path.rsplit_once('/') {
+            Some((_, "")) | None => &self.path,
+            Some((_, name)) => name,
+        }
+    }
+}
+
+/// The finished filesystem, with the total size of every directory known
+#[derive(Debug)]
+pub struct FileSystem {
+    tree: Tree<Directory>,
+}
+impl FileSystem {
+    /// Replay every line in turn
+    pub fn replay<I: IntoIterator<Item = TerminalLine>>(lines: I) -> Result<Self, ReplayError> {
+        let mut shell = Shell::new();
+        for line in lines {
+            shell.run(line)?;
+        }
+        Ok(shell.finish())
+    }
+
+    /// Total size of everything in and below the root
+    pub fn used_space(&self) -> usize {
+        self.root().get_full_size()
+    }
+
+    /// Size of the file or directory at an absolute path
+    pub fn size_of(&self, path: &str) -> Option<usize> {
+        let mut node_id = self.tree.root_node_id().unwrap().clone();
+        let mut components = path.split('/').filter(|name| !name.is_empty()).peekable();
+        while let Some(name) = components.next() {
+            match find_child(&self.tree, &node_id, name) {
+                Some(child) => node_id = child,
+                // Only the last component can be a file
+                None if components.peek().is_none() => {
+                    return self.directory(&node_id).file(name).map(|file| file.size());
+                }
+                None => return None,
+            }
+        }
+        Some(self.directory(&node_id).get_full_size())
+    }
+
+    /// Every directory and file, each directory followed by its files and then its
+    /// subdirectories
+    pub fn walk(&self) -> impl Iterator<Item = Entry> + '_ {
+        self.tree
+            .traverse_pre_order_ids(self.tree.root_node_id().unwrap())
+            .unwrap()
+            .flat_map(move |node_id| {
+                let dir = self.directory(&node_id);
+                let path = path_of(&self.tree, &node_id);
+                let depth = self.tree.ancestor_ids(&node_id).unwrap().count();
+                let files = dir.files().iter().map(move |file| Entry {
+                    path: join(&path, file.name()),
+                    size: file.size(),
+                    kind: EntryKind::File,
+                    depth: depth + 1,
+                });
+                std::iter::once(Entry {
+                    path: path_of(&self.tree, &node_id),
+                    size: dir.get_full_size(),
+                    kind: EntryKind::Directory,
+                    depth,
+                })
+                .chain(files)
+            })
+    }
+
+    /// Every entry matching `predicate`, in the order of `walk`
+    pub fn find<'a, P>(&'a self, predicate: P) -> impl Iterator<Item = Entry> + 'a
+    where
+        P: FnMut(&Entry) -> bool + 'a,
+    {
+        self.walk().filter(predicate)
+    }
+
+    /// Every directory, and every file too if `all`, largest first like `du | sort -rh`
+    pub fn disk_usage(&self, all: bool) -> Vec<Entry> {
+        let mut entries: Vec<Entry> = self.find(move |entry| all || entry.is_dir()).collect();
+        entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        entries
+    }
+
+    /// The hierarchy drawn like `tree`, with the same annotations as the puzzle
+    ///
+    /// ```text
+    /// / (dir, size=48381165)
+    /// ├── a (dir, size=94853)
+    /// │   └── f (file, size=29116)
+    /// └── b.txt (file, size=14848514)
+    /// ```
+    pub fn render_tree(&self) -> String {
+        let root_id = self.tree.root_node_id().unwrap();
+        let mut out = format!("/ (dir, size={})\n", self.root().get_full_size());
+        self.render_children(root_id, "", &mut out);
+        out
+    }
+
+    fn render_children(&self, node_id: &NodeId, prefix: &str, out: &mut String) {
+        let children = self.children(node_id);
+        for (idx, child) in children.iter().enumerate() {
+            let last = idx + 1 == children.len();
+            let (branch, indent) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            match child {
+                Child::Directory(child_id) => {
+                    let dir = self.directory(child_id);
+                    out.push_str(&format!(
+                        "{}{}{} (dir, size={})\n",
+                        prefix,
+                        branch,
+                        dir.name(),
+                        dir.get_full_size()
+                    ));
+                    self.render_children(child_id, &format!("{}{}", prefix, indent), out);
+                }
+                Child::File(file) => out.push_str(&format!(
+                    "{}{}{} (file, size={})\n",
+                    prefix,
+                    branch,
+                    file.name(),
+                    file.size()
+                )),
+            }
+        }
+    }
+
+    /// The whole tree as pretty-printed JSON, with the total size of each directory
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(self.tree.root_node_id().unwrap(), 0, &mut out);
+        out.push('\n');
+        out
+    }
+
+    fn write_json(&self, node_id: &NodeId, depth: usize, out: &mut String) {
+        let dir = self.directory(node_id);
+        let indent = "  ".repeat(depth + 1);
+        out.push_str(&format!(
+            "{{\n{indent}\"name\": {},\n{indent}\"type\": \"dir\",\n{indent}\"size\": {},\n{indent}\"children\": [",
+            json_string(dir.name()),
+            dir.get_full_size(),
+            indent = indent
+        ));
+        let children = self.children(node_id);
+        for (idx, child) in children.iter().enumerate() {
+            out.push_str(if idx == 0 { "\n" } else { ",\n" });
+            out.push_str(&indent);
+            out.push_str("  ");
+            match child {
+                Child::Directory(child_id) => self.write_json(child_id, depth + 2, out),
+                Child::File(file) => out.push_str(&format!(
+                    "{{ \"name\": {}, \"type\": \"file\", \"size\": {} }}",
+                    json_string(file.name()),
+                    file.size()
+                )),
+            }
+        }
+        if !children.is_empty() {
+            out.push('\n');
+            out.push_str(&indent);
+        }
+        out.push_str("]\n");
+        out.push_str(&"  ".repeat(depth));
+        out.push('}');
+    }
+
+    /// Subdirectories and files of a directory, sorted by name
+    fn children(&self, node_id: &NodeId) -> Vec<Child<'_>> {
+        let mut children: Vec<Child> = self
+            .tree
+            .children_ids(node_id)
+            .unwrap()
+            .map(|child_id| Child::Directory(child_id.clone()))
+            .chain(self.directory(node_id).files().iter().map(Child::File))
+            .collect();
+        children.sort_by(|a, b| self.child_name(a).cmp(self.child_name(b)));
+        children
+    }
+
+    fn child_name<'a>(&'a self, child: &'a Child) -> &'a str {
+        match child {
+            Child::Directory(child_id) => self.directory(child_id).name(),
+            Child::File(file) => file.name(),
+        }
+    }
+
+    fn root(&self) -> &Directory {
+        self.directory(self.tree.root_node_id().unwrap())
+    }
+
+    fn directory(&self, node_id: &NodeId) -> &Directory {
+        self.tree.get(node_id).unwrap().data()
+    }
+}
+
+/// Something inside a directory
+enum Child<'a> {
+    Directory(NodeId),
+    File(&'a LocalFile),
+}
+
+/// Quote a string for JSON
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A size like `du -h` prints it, in powers of 1024 and rounded up
+pub fn human_size(size: usize) -> String {
+    const UNITS: [&str; 6] = ["", "K", "M", "G", "T", "P"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        size.to_string()
+    } else if value < 10.0 && (value * 10.0).ceil() < 100.0 {
+        format!("{:.1}{}", (value * 10.0).ceil() / 10.0, UNITS[unit])
+    } else {
+        format!("{:.0}{}", value.ceil(), UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DaySeven;
+    use aoc_utils::Solution;
+
+    fn replay(input: &str) -> FileSystem {
+        DaySeven::parse(input.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_replay() {
+        let fs = replay(
+            "$ cd /a/b
+$ ls
+10 x
+$ ls
+10 x
+20 y
+$ cd ../../c
+$ ls
+5 z
+$ cd /
+$ cd ./a/./b/..
+$ ls
+dir b
+1 w",
+        );
+        assert_eq!(fs.used_space(), 36);
+        assert_eq!(fs.size_of("/a"), Some(31));
+        assert_eq!(fs.size_of("/a/b"), Some(30));
+        assert_eq!(fs.size_of("/a/b/y"), Some(20));
+        assert_eq!(fs.size_of("/c/"), Some(5));
+        assert_eq!(fs.size_of("/d"), None);
+
+        let paths: Vec<String> = fs.walk().map(|entry| entry.path).collect();
+        assert_eq!(
+            paths,
+            vec!["/", "/a", "/a/w", "/a/b", "/a/b/x", "/a/b/y", "/c", "/c/z"]
+        );
+        let large: Vec<String> = fs
+            .find(|entry| !entry.is_dir() && entry.size >= 10)
+            .map(|entry| entry.name().to_string())
+            .collect();
+        assert_eq!(large, vec!["x", "y"]);
+    }
+
+    #[test]
+    fn test_reports() {
+        let fs = replay("$ cd /\n$ ls\ndir b\n3 \"q\"\n$ cd b\n$ ls\n2048 z\n1500 a");
+        assert_eq!(
+            fs.render_tree(),
+            "/ (dir, size=3551)
+├── \"q\" (file, size=3)
+└── b (dir, size=3548)
+    ├── a (file, size=1500)
+    └── z (file, size=2048)
+"
+        );
+        let du: Vec<(String, usize)> = fs
+            .disk_usage(true)
+            .into_iter()
+            .map(|entry| (entry.path, entry.size))
+            .collect();
+        assert_eq!(
+            du[..3],
+            [
+                ("/".to_string(), 3551),
+                ("/b".to_string(), 3548),
+                ("/b/z".to_string(), 2048)
+            ]
+        );
+        assert_eq!(fs.disk_usage(false).len(), 2);
+
+        let json = fs.to_json();
+        assert!(json.starts_with("{\n  \"name\": \"/\",\n  \"type\": \"dir\",\n  \"size\": 3551,"));
+        assert!(json.contains("{ \"name\": \"\\\"q\\\"\", \"type\": \"file\", \"size\": 3 }"));
+        assert!(json.ends_with("]\n}\n"));
+
+        assert_eq!(human_size(1000), "1000");
+        assert_eq!(human_size(1025), "1.1K");
+        assert_eq!(human_size(48381165), "47M");
+        assert_eq!(human_size(10 * 1024 - 1), "10K");
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let err = DaySeven::parse("$ ls\n5 a\n$ cd a".as_bytes()).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.message, "`/a` is a file, not a directory");
+        let err = DaySeven::parse("$ ls\ndir a\n5 a".as_bytes()).unwrap_err();
+        assert_eq!(err.message, "`/a` is a directory, not a file");
+    }
+}
@@ -0,0 +1,151 @@
+//! Solutions for part one and part two
+use std::io::BufRead;
+
+use aoc_utils::{next_token, parse_token, try_read_lines, ParseError, Solution};
+
+pub mod cleanup;
+pub mod filesystem;
+use cleanup::{smallest_directory, Capacity};
+use filesystem::{Directory, FileSystem, LocalFile, Shell};
+
+type ReturnType = usize;
+type VectorType = TerminalLine;
+
+/// Solution for day seven
+pub struct DaySeven;
+impl Solution for DaySeven {
+    type Input = FileSystem;
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        let mut shell = Shell::new();
+        for (idx, line) in try_read_lines(reader)?.iter().enumerate() {
+            let terminal_line = map_one(line).map_err(|e| e.with_line(idx + 1))?;
+            shell.run(terminal_line).map_err(|e| {
+                // The name or path is always the last word
+                let name = line.split_whitespace().last().unwrap_or(line);
+                ParseError::at_token(line, name, e.to_string()).with_line(idx + 1)
+            })?;
+        }
+        Ok(shell.finish())
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        Ok(part_one_internal(input))
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        Ok(part_two_internal(input))
+    }
+}
+
+fn parse_file(input: &str) -> Result<LocalFile, ParseError> {
+    let mut words = input.split_whitespace();
+    let size: usize = parse_token(input, next_token(input, &mut words, "file size")?)?;
+    let name = next_token(input, &mut words, "file name")?;
+    Ok(LocalFile::new(name, size))
+}
+
+fn parse_directory(input: &str) -> Result<Directory, ParseError> {
+    let mut words = input.split_whitespace().skip(1);
+    let name = next_token(input, &mut words, "directory name")?;
+    Ok(Directory::new(name))
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Cd(String),
+    Ls,
+}
+impl Command {
+    fn try_new(input: &str) -> Result<Self, ParseError> {
+        let mut words = input.split_whitespace().skip(1);
+        let cmd = next_token(input, &mut words, "command")?;
+        match cmd {
+            "cd" => Ok(Command::Cd(
+                next_token(input, &mut words, "directory to change to")?.to_string(),
+            )),
+            "ls" => Ok(Command::Ls),
+            _ => Err(ParseError::at_token(
+                input,
+                cmd,
+                format!("unknown command `{}`", cmd),
+            )),
+        }
+    }
+}
+
+/// A single line of terminal output
+#[derive(Debug)]
+pub enum TerminalLine {
+    Command(Command),
+    Directory(Directory),
+    File(LocalFile),
+}
+
+/// Map a line to a VectorType
+fn map_one(input: &str) -> Result<VectorType, ParseError> {
+    match input.split_whitespace().next() {
+        Some("$") => Command::try_new(input).map(TerminalLine::Command),
+        Some("dir") => parse_directory(input).map(TerminalLine::Directory),
+        Some(_) => parse_file(input).map(TerminalLine::File),
+        None => Err(ParseError::missing(input, "a command or directory listing")),
+    }
+}
+
+fn part_one_internal(input: FileSystem) -> ReturnType {
+    input
+        .find(|entry| entry.is_dir() && entry.size <= 100000)
+        .map(|entry| entry.size)
+        .sum()
+}
+
+fn part_two_internal(input: FileSystem) -> ReturnType {
+    smallest_directory(&input, &Capacity::default())
+        .expect("the update fits on the disk, so deleting `/` is always enough")
+        .reclaimed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> &'static str {
+        "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k"
+    }
+
+    #[test]
+    fn test_one() {
+        let input = DaySeven::parse(input().as_bytes()).unwrap();
+        assert_eq!(part_one_internal(input), 95437);
+    }
+
+    #[test]
+    fn test_two() {
+        let input = DaySeven::parse(input().as_bytes()).unwrap();
+        assert_eq!(part_two_internal(input), 24933642);
+    }
+}
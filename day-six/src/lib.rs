@@ -0,0 +1,139 @@
+//! Solutions for part one and part two
+use std::io::BufRead;
+
+use aoc_utils::{ParseError, Solution};
+
+pub mod marker;
+use marker::first_markers;
+
+/// Number of distinct characters in a start-of-packet and a start-of-message marker
+const WINDOWS: [usize; 2] = [4, 14];
+
+/// Where the first start-of-packet and start-of-message markers end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FirstMarkers {
+    pub packet: usize,
+    pub message: usize,
+}
+
+/// Stream the first line of `reader` through a detector for each kind of marker
+fn parse_input<R: BufRead>(reader: R) -> Result<FirstMarkers, ParseError> {
+    let found = first_markers(reader, WINDOWS)
+        .map_err(|e| ParseError::input(format!("cannot read datastream: {}", e)).with_line(1))?;
+    let mut positions = WINDOWS.iter().zip(found).map(|(window, position)| {
+        position.ok_or_else(|| {
+            ParseError::input(format!(
+                "no marker of {} different characters in the datastream",
+                window
+            ))
+            .with_line(1)
+        })
+    });
+    Ok(FirstMarkers {
+        packet: positions.next().unwrap()?,
+        message: positions.next().unwrap()?,
+    })
+}
+
+type ReturnType = usize;
+
+/// Solution for day six
+pub struct DaySix;
+impl Solution for DaySix {
+    type Input = FirstMarkers;
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        parse_input(reader)
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        Ok(part_one_internal(input))
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        Ok(part_two_internal(input))
+    }
+}
+
+fn part_one_internal(input: FirstMarkers) -> ReturnType {
+    input.packet
+}
+
+fn part_two_internal(input: FirstMarkers) -> ReturnType {
+    input.message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use marker::{Marker, Markers};
+
+    fn input<'a>() -> &'a str {
+        "mjqjpqmgbljsphdztnvjfqwrcgsmlb
+bvwbjplbgvbhsrlpgdmjqwftvncz
+nppdvjthqldpwncqszvftbrmjlhg
+nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg
+zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"
+    }
+
+    /// Position of the first marker on each line
+    fn first_on_each_line(window: usize) -> Vec<usize> {
+        let mut firsts = Vec::new();
+        for marker in Markers::new(input().as_bytes(), window, false) {
+            let marker = marker.unwrap();
+            if firsts.len() < marker.line {
+                firsts.push(marker.position);
+            }
+        }
+        firsts
+    }
+
+    #[test]
+    fn test_one() {
+        assert_eq!(first_on_each_line(4), [7, 5, 6, 10, 11]);
+        let input = DaySix::parse(input().as_bytes()).unwrap();
+        assert_eq!(part_one_internal(input), 7);
+    }
+
+    #[test]
+    fn test_two() {
+        assert_eq!(first_on_each_line(14), [19, 23, 23, 29, 26]);
+        let input = DaySix::parse(input().as_bytes()).unwrap();
+        assert_eq!(part_two_internal(input), 19);
+    }
+
+    #[test]
+    fn test_no_marker() {
+        let err = DaySix::parse("abcdabcdabcdabcd\nabcdefghijklmn".as_bytes()).unwrap_err();
+        assert_eq!(err.line, 1);
+        assert!(err.message.contains("14 different"));
+        assert!(DaySix::parse("".as_bytes()).is_err());
+        // Reading stops at the end of the first line
+        let found = first_markers("aaaa\nabcd".as_bytes(), [4]).unwrap();
+        assert_eq!(found, [None]);
+    }
+
+    #[test]
+    fn test_every_marker() {
+        let positions = |text: &str, window, joined| -> Vec<(usize, usize)> {
+            Markers::new(text.as_bytes(), window, joined)
+                .map(|marker| marker.unwrap())
+                .map(|Marker { line, position }| (line, position))
+                .collect()
+        };
+        assert_eq!(positions("abca", 3, false), [(1, 3), (1, 4)]);
+        assert_eq!(positions("aab\r\nbc", 2, false), [(1, 3), (2, 2)]);
+        // Joined up, the window carries on over the line break
+        assert_eq!(positions("aab\nbc", 2, true), [(1, 3), (2, 5)]);
+        assert_eq!(positions("a", 1, false), [(1, 1)]);
+        // Reading through a tiny buffer gives the same as reading all at once
+        let reader = std::io::BufReader::with_capacity(3, input().as_bytes());
+        let all: Vec<_> = Markers::new(reader, 4, true).map(|m| m.unwrap()).collect();
+        let expected: Vec<_> = Markers::new(input().as_bytes(), 4, true)
+            .map(|m| m.unwrap())
+            .collect();
+        assert_eq!(all, expected);
+    }
+}
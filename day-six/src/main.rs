@@ -1,95 +1,73 @@
 //! Command line executable for running part one and part two
-use anyhow::{anyhow, Result};
-use std::{
-    collections::HashSet,
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::io::{self, BufRead};
 
+use aoc_utils::{exit_with, open_or_exit, solve, Part};
 use clap::Parser;
+use day_six::{marker::Markers, DaySix};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input file
+    /// Input file, or `-` to read from standard input
     #[arg(short)]
     input_file: String,
 
     #[command(subcommand)]
-    part: Part,
+    command: Command,
 }
 
 #[derive(clap::Subcommand, Debug)]
-enum Part {
-    Part1,
-    Part2,
+enum Command {
+    #[command(flatten)]
+    Part(Part),
+    /// Report every marker in the datastream as it is read
+    Markers {
+        /// Number of distinct characters that make a marker
+        #[arg(short, long, default_value_t = 4, value_parser = clap::value_parser!(u64).range(1..))]
+        window: u64,
+
+        /// Treat the lines as one datastream rather than each line as its own
+        #[arg(short, long)]
+        joined: bool,
+    },
 }
 
-fn main() {
-    let args = Args::parse();
-
-    let file = BufReader::new(File::open(args.input_file).expect("Cannot find file"));
-
-    let answer = match args.part {
-        Part::Part1 => part_one(file),
-        Part::Part2 => part_two(file),
-    };
-
-    println!("{:?}", answer);
-}
-
-fn part_one(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file);
-    part_one_internal(input)
-}
-
-fn part_two(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file);
-    part_two_internal(input)
-}
-
-fn parse_input(file: BufReader<File>) -> String {
-    file.lines().next().unwrap().unwrap()
-}
-
-// TODO -- Update this with the return type
-type ReturnType = usize;
-
-// TODO Implement this
-fn part_one_internal(input: String) -> ReturnType {
-    find_first_unique(input, 4).unwrap()
-}
-
-fn part_two_internal(input: String) -> ReturnType {
-    find_first_unique(input, 14).unwrap()
-}
-
-fn find_first_unique(input: String, window_size: usize) -> Result<ReturnType> {
-    let chars: Vec<char> = input.chars().collect();
-
-    for (idx, window) in chars.windows(window_size).enumerate() {
-        if is_all_unique(window) {
-            return Ok(idx + window_size);
-        }
+/// Standard input for `-`, or else the input file, exiting if it cannot be opened
+fn open_input(filename: &str) -> Box<dyn BufRead> {
+    if filename == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(open_or_exit(filename))
     }
-    Err(anyhow!("Could not find unique"))
 }
 
-fn is_all_unique(input: &[char]) -> bool {
-    let mut hash = HashSet::new();
-    for c in input {
-        if !hash.insert(c) {
-            return false;
-        }
+/// Print the line and position of every marker in `reader`
+fn print_markers<R: BufRead>(reader: R, window: usize, joined: bool) -> io::Result<()> {
+    for marker in Markers::new(reader, window, joined) {
+        let marker = marker?;
+        println!(
+            "line {}: marker after character {}",
+            marker.line, marker.position
+        );
     }
-    true
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn test_one() {}
+fn main() {
+    let args = Args::parse();
 
-    #[test]
-    fn test_two() {}
+    let reader = open_input(&args.input_file);
+    match args.command {
+        Command::Part(part) => match solve::<DaySix, _>(reader, part) {
+            Ok(answer) => println!("{}", answer),
+            Err(e) if args.input_file == "-" => exit_with(e.with_file("<stdin>")),
+            Err(e) => exit_with(e.with_file(&args.input_file)),
+        },
+        Command::Markers { window, joined } => {
+            if let Err(e) = print_markers(reader, window as usize, joined) {
+                eprintln!("error: cannot read {}: {}", args.input_file, e);
+                std::process::exit(1);
+            }
+        }
+    }
 }
@@ -0,0 +1,174 @@
+//! Finding markers, runs of distinct characters, while reading a datastream.
+//!
+//! The detector keeps a count of each byte in the window and how many distinct bytes that is,
+//! so each byte read costs the same however wide the window is, and nothing but the window is
+//! kept in memory.
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead},
+};
+
+/// Tracks the last `window` bytes of a datastream
+#[derive(Debug, Clone)]
+pub struct MarkerDetector {
+    window: usize,
+    recent: VecDeque<u8>,
+    counts: [usize; 256],
+    distinct: usize,
+    position: usize,
+}
+impl MarkerDetector {
+    /// Panics if `window` is zero
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "a marker needs at least one character");
+        Self {
+            window,
+            recent: VecDeque::with_capacity(window),
+            counts: [0; 256],
+            distinct: 0,
+            position: 0,
+        }
+    }
+
+    /// Forget everything, ready for a new datastream
+    pub fn reset(&mut self) {
+        self.recent.clear();
+        self.counts = [0; 256];
+        self.distinct = 0;
+        self.position = 0;
+    }
+
+    /// Number of bytes read since the start of the datastream
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Read one byte and return whether it completes a marker
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.recent.len() == self.window {
+            let oldest = self.recent.pop_front().unwrap();
+            self.counts[oldest as usize] -= 1;
+            if self.counts[oldest as usize] == 0 {
+                self.distinct -= 1;
+            }
+        }
+        self.recent.push_back(byte);
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+        self.position += 1;
+        self.distinct == self.window
+    }
+}
+
+/// Where a marker ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Marker {
+    /// Line of the input, counting from 1
+    pub line: usize,
+    /// Characters read from the datastream up to the end of the marker, as the puzzle counts
+    pub position: usize,
+}
+
+/// Every marker in a reader, found as it is read
+///
+/// Each line is its own datastream unless `joined`, in which case line breaks are skipped and
+/// the lines make up one datastream.
+pub struct Markers<R> {
+    reader: R,
+    detector: MarkerDetector,
+    joined: bool,
+    line: usize,
+}
+impl<R: BufRead> Markers<R> {
+    pub fn new(reader: R, window: usize, joined: bool) -> Self {
+        Self {
+            reader,
+            detector: MarkerDetector::new(window),
+            joined,
+            line: 1,
+        }
+    }
+}
+impl<R: BufRead> Iterator for Markers<R> {
+    type Item = io::Result<Marker>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let buf = match self.reader.fill_buf() {
+                Ok([]) => return None,
+                Ok(buf) => buf,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            let mut found = None;
+            let mut used = 0;
+            for &byte in buf {
+                used += 1;
+                match byte {
+                    b'\n' => {
+                        self.line += 1;
+                        if !self.joined {
+                            self.detector.reset();
+                        }
+                    }
+                    b'\r' => (),
+                    byte if self.detector.push(byte) => {
+                        found = Some(Marker {
+                            line: self.line,
+                            position: self.detector.position(),
+                        });
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+            self.reader.consume(used);
+            if found.is_some() {
+                return found.map(Ok);
+            }
+        }
+    }
+}
+
+/// Where the first marker of each window size ends on the first line of `reader`
+///
+/// The line is read once, with a detector for every window running alongside each other, and
+/// reading stops as soon as every window has found its marker.
+pub fn first_markers<R: BufRead, const N: usize>(
+    mut reader: R,
+    windows: [usize; N],
+) -> io::Result<[Option<usize>; N]> {
+    let mut detectors = windows.map(MarkerDetector::new);
+    let mut found = [None; N];
+    loop {
+        let buf = match reader.fill_buf() {
+            Ok(buf) => buf,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if buf.is_empty() {
+            return Ok(found);
+        }
+        let mut used = 0;
+        for &byte in buf {
+            used += 1;
+            match byte {
+                b'\n' => return Ok(found),
+                b'\r' => (),
+                byte => {
+                    for (detector, found) in detectors.iter_mut().zip(found.iter_mut()) {
+                        if detector.push(byte) && found.is_none() {
+                            *found = Some(detector.position());
+                        }
+                    }
+                    if found.iter().all(Option::is_some) {
+                        return Ok(found);
+                    }
+                }
+            }
+        }
+        reader.consume(used);
+    }
+}
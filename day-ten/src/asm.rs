@@ -0,0 +1,609 @@
+//! An assembler for writing day ten programs by hand, and a listing of what a program does.
+//!
+//! Source is one instruction per line, with `;` starting a comment. A line may begin with a
+//! `label:`, which names the instruction after it. On top of the instructions themselves there
+//! are macros, all expanding to `noop` and `addx`:
+//!
+//! - `wait <n>` runs `n` noops
+//! - `set <x>` changes `X` to `x`
+//! - `until <cycle>` runs noops until the next instruction starts on `cycle`
+//! - `text <LETTERS>` draws the letters across the screen
+//! - `bitmap` followed by rows of `#` and `.` and then `end` draws the picture
+//! - `macro <name>`, some lines and `end` defines a macro that `<name>` then expands to
+//!
+//! Labels inside a macro are local to each expansion, with the number of the expansion added,
+//! so a `top:` in the second macro to be expanded becomes `top.2`.
+//!
+//! The CRT draws whatever `X` allows while the program runs, so the drawing macros make the
+//! program run until the screen is finished, and only control pixels from the current cycle on.
+use std::{collections::HashMap, io::BufRead};
+
+use aoc_utils::{next_token, parse_token, try_read_lines, Grid, ParseError};
+
+use crate::{
+    cpu::{Crt, Instruction, InstructionSet},
+    glyphs, SCREEN_HEIGHT, SCREEN_WIDTH,
+};
+
+/// How deeply macros may expand inside one another
+const MAX_DEPTH: usize = 16;
+
+/// Instructions and the labels naming some of them
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Program {
+    pub instructions: Vec<Instruction>,
+    /// Each label with the index of the instruction after it
+    pub labels: Vec<(String, usize)>,
+}
+
+/// Assemble source into a program of instructions from `set`, which needs `noop` and `addx`
+pub fn assemble<R: BufRead>(set: &InstructionSet, reader: R) -> Result<Program, ParseError> {
+    let lines: Vec<_> = try_read_lines(reader)?
+        .into_iter()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .collect();
+    let mut assembler = Assembler {
+        set,
+        noop: lookup(set, "noop")?,
+        addx: lookup(set, "addx")?,
+        program: Program::default(),
+        macros: HashMap::new(),
+        expansions: 0,
+        cycle: 0,
+        x: 1,
+    };
+    assembler.block(&lines, 0, None)?;
+    Ok(assembler.program)
+}
+
+fn lookup(set: &InstructionSet, name: &str) -> Result<usize, ParseError> {
+    set.lookup(name).ok_or_else(|| {
+        ParseError::input(format!(
+            "the instruction set needs `{}` for the macros",
+            name
+        ))
+    })
+}
+
+/// A line of source without its comment
+fn strip_comment(line: &str) -> &str {
+    line.split(';').next().unwrap_or_default()
+}
+
+/// Lines numbered from 1, as the assembler sees them
+type Lines = [(usize, String)];
+
+struct Assembler<'a> {
+    set: &'a InstructionSet,
+    noop: usize,
+    addx: usize,
+    program: Program,
+    macros: HashMap<String, Vec<(usize, String)>>,
+    /// Macros expanded so far, which numbers the labels inside them
+    expansions: usize,
+    /// Cycles the program has run so far
+    cycle: usize,
+    /// `X` once those cycles are done
+    x: i64,
+}
+impl<'a> Assembler<'a> {
+    fn emit(&mut self, instruction: Instruction) {
+        let opcode = self.set.opcode(instruction.opcode);
+        self.cycle += opcode.cycles;
+        self.x = (opcode.effect)(self.x, &instruction.args);
+        self.program.instructions.push(instruction);
+    }
+
+    fn noops(&mut self, count: usize) {
+        for _ in 0..count {
+            self.emit(Instruction {
+                opcode: self.noop,
+                args: Vec::new(),
+            });
+        }
+    }
+
+    fn addx(&mut self, amount: i64) {
+        self.emit(Instruction {
+            opcode: self.addx,
+            args: vec![amount],
+        });
+    }
+
+    /// Assemble `lines`, taking the bodies of `macro` and `bitmap` from the lines after them
+    ///
+    /// Inside a macro, `expansion` is the number of the expansion to add to its labels.
+    fn block(
+        &mut self,
+        lines: &Lines,
+        depth: usize,
+        expansion: Option<usize>,
+    ) -> Result<(), ParseError> {
+        let mut idx = 0;
+        while idx < lines.len() {
+            let (line_num, line) = &lines[idx];
+            idx += 1;
+            let text = strip_comment(line);
+            let mut words = text.split_whitespace().peekable();
+            if let Some(label) = words.next_if(|word| word.ends_with(':')) {
+                self.label(line, label, expansion)
+                    .map_err(|e| e.with_line(*line_num))?;
+            }
+            if let Some(label) = words.next_if(|word| word.ends_with(':')) {
+                return Err(
+                    ParseError::at_token(line, label, "a line can only have one label")
+                        .with_line(*line_num),
+                );
+            }
+            let Some(&keyword) = words.peek() else {
+                continue;
+            };
+            let result = match keyword {
+                "macro" | "bitmap" => {
+                    let body = body(&lines[idx..])
+                        .ok_or_else(|| ParseError::missing(line, "`end`").with_line(*line_num))?;
+                    idx += body.len() + 1;
+                    if keyword == "macro" {
+                        self.define(line, words.nth(1), body)
+                    } else {
+                        self.bitmap(line, body, *line_num)
+                    }
+                }
+                _ => self.statement(line, text, depth),
+            };
+            result.map_err(|e| e.with_line(*line_num))?;
+        }
+        Ok(())
+    }
+
+    fn label(
+        &mut self,
+        line: &str,
+        label: &str,
+        expansion: Option<usize>,
+    ) -> Result<(), ParseError> {
+        let name = &label[..label.len() - 1];
+        // Labels from an expansion come back numbered when a listing is assembled again
+        let (base, number) = match name.split_once('.') {
+            Some(_) if expansion.is_some() => ("", ""),
+            Some(parts) => parts,
+            None => (name, "0"),
+        };
+        let valid = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        if base.is_empty()
+            || !base.chars().all(valid)
+            || number.is_empty()
+            || !number.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(ParseError::at_token(
+                line,
+                label,
+                format!("`{}` is not a valid label", name),
+            ));
+        }
+        let name = match expansion {
+            Some(number) => format!("{}.{}", name, number),
+            None => name.to_string(),
+        };
+        if self.program.labels.iter().any(|(other, _)| *other == name) {
+            return Err(ParseError::at_token(
+                line,
+                label,
+                format!("label `{}` is already defined", name),
+            ));
+        }
+        self.program
+            .labels
+            .push((name, self.program.instructions.len()));
+        Ok(())
+    }
+
+    fn define(&mut self, line: &str, name: Option<&str>, body: &Lines) -> Result<(), ParseError> {
+        let name = name.ok_or_else(|| ParseError::missing(line, "a macro name"))?;
+        let reserved = ["macro", "bitmap", "end", "wait", "set", "until", "text"];
+        if reserved.contains(&name) || self.set.lookup(name).is_some() {
+            return Err(ParseError::at_token(
+                line,
+                name,
+                format!("`{}` is already an instruction", name),
+            ));
+        }
+        self.macros.insert(name.to_string(), body.to_vec());
+        Ok(())
+    }
+
+    /// A line holding a single instruction or macro call
+    fn statement(&mut self, line: &str, text: &str, depth: usize) -> Result<(), ParseError> {
+        let mut words = text
+            .split_whitespace()
+            .skip_while(|word| word.ends_with(':'));
+        let keyword = next_token(line, &mut words, "an instruction")?;
+        let builtin = ["wait", "set", "until", "text"].contains(&keyword);
+        let argument = if builtin {
+            next_token(line, &mut words, "an argument")?
+        } else {
+            ""
+        };
+        // The instruction set checks the arguments of everything that isn't a macro
+        let takes = if builtin {
+            Some("one argument")
+        } else if self.macros.contains_key(keyword) {
+            Some("no arguments")
+        } else {
+            None
+        };
+        if let Some((takes, extra)) = takes.zip(words.next()) {
+            return Err(ParseError::at_token(
+                line,
+                extra,
+                format!("`{}` takes {}", keyword, takes),
+            ));
+        }
+        match keyword {
+            "wait" => {
+                let count = parse_token(line, argument)?;
+                self.noops(count);
+            }
+            "set" => {
+                let x: i64 = parse_token(line, argument)?;
+                self.addx(x - self.x);
+            }
+            "until" => {
+                let cycle: usize = parse_token(line, argument)?;
+                if cycle <= self.cycle {
+                    return Err(ParseError::at_token(
+                        line,
+                        argument,
+                        format!(
+                            "the next instruction already starts on cycle {}",
+                            self.cycle + 1
+                        ),
+                    ));
+                }
+                self.noops(cycle - self.cycle - 1);
+            }
+            "text" => {
+                let token = argument;
+                let screen = glyphs::draw(token, SCREEN_WIDTH);
+                if glyphs::decode(&screen).trim_end_matches('?') != token {
+                    return Err(ParseError::at_token(
+                        line,
+                        token,
+                        format!("cannot draw `{}` with the known letters", token),
+                    ));
+                }
+                self.draw(line, token, &screen)?;
+            }
+            "end" => {
+                return Err(ParseError::at_token(
+                    line,
+                    keyword,
+                    "`end` without `macro` or `bitmap`",
+                ))
+            }
+            name => match self.macros.get(name) {
+                Some(body) => {
+                    if depth >= MAX_DEPTH {
+                        return Err(ParseError::at_token(
+                            line,
+                            name,
+                            format!("macro `{}` expands too deeply", name),
+                        ));
+                    }
+                    let body = body.clone();
+                    self.expansions += 1;
+                    self.block(&body, depth + 1, Some(self.expansions))?;
+                }
+                None => {
+                    // Anything else goes to the instruction set, without the label
+                    let start = name.as_ptr() as usize - line.as_ptr() as usize;
+                    let instruction =
+                        self.set
+                            .parse_line(&line[start..text.len()])
+                            .map_err(|mut e| {
+                                e.column += line[..start].chars().count();
+                                e.line_text = line.to_string();
+                                e
+                            })?;
+                    self.emit(instruction);
+                }
+            },
+        }
+        Ok(())
+    }
+
+    fn bitmap(&mut self, line: &str, rows: &Lines, line_num: usize) -> Result<(), ParseError> {
+        let mut screen = Grid::filled(SCREEN_HEIGHT, SCREEN_WIDTH, false);
+        for (row, (row_num, raw)) in rows.iter().enumerate() {
+            let text = strip_comment(raw).trim();
+            if row >= SCREEN_HEIGHT {
+                return Err(ParseError::at_token(
+                    raw,
+                    text,
+                    format!("the screen is only {} rows high", SCREEN_HEIGHT),
+                )
+                .with_line(*row_num));
+            }
+            for (col, (offset, c)) in text.char_indices().enumerate() {
+                let message = if col >= SCREEN_WIDTH {
+                    format!("the screen is only {} pixels wide", SCREEN_WIDTH)
+                } else if !matches!(c, '#' | '.') {
+                    format!("expected `#` or `.` but found `{}`", c)
+                } else {
+                    screen[(row, col)] = c == '#';
+                    continue;
+                };
+                let token = &text[offset..offset + c.len_utf8()];
+                return Err(ParseError::at_token(raw, token, message).with_line(*row_num));
+            }
+        }
+        self.draw(line, "bitmap", &screen)
+            .map_err(|e| e.with_line(line_num))
+    }
+
+    /// Emit the instructions that draw `screen` from the current cycle on
+    fn draw(&mut self, line: &str, token: &str, screen: &Grid<bool>) -> Result<(), ParseError> {
+        let steps = drawing(screen, self.cycle, self.x).ok_or_else(|| {
+            ParseError::at_token(line, token, "the CRT cannot draw this from here")
+        })?;
+        for step in steps {
+            match step {
+                Some(amount) => self.addx(amount),
+                None => self.noops(1),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The lines up to the `end` closing a block, allowing for blocks inside it
+fn body(lines: &Lines) -> Option<&Lines> {
+    let mut open = 1;
+    for (idx, (_, line)) in lines.iter().enumerate() {
+        match strip_comment(line).split_whitespace().next() {
+            Some("macro" | "bitmap") => open += 1,
+            Some("end") => open -= 1,
+            _ => (),
+        }
+        if open == 0 {
+            return Some(&lines[..idx]);
+        }
+    }
+    None
+}
+
+/// Noops (`None`) and addx amounts that make the CRT draw `screen`, starting `start` cycles in
+/// with `X` at `x`
+///
+/// `X` can only change as an addx finishes, so it holds each value for at least two cycles.
+/// This finds the cycles where it changes, from the first pixel on, and the value it holds on
+/// each stretch. Beyond the edges of the screen every value draws the same, so only those from
+/// just off the left to just off the right need trying.
+fn drawing(screen: &Grid<bool>, start: usize, x: i64) -> Option<Vec<Option<i64>>> {
+    let n_pixels = SCREEN_WIDTH * SCREEN_HEIGHT;
+    if start >= n_pixels {
+        return Some(Vec::new());
+    }
+    let fits = |x: i64, pixel: usize| {
+        Crt::is_lit(x, pixel % SCREEN_WIDTH) == screen[(pixel / SCREEN_WIDTH, pixel % SCREEN_WIDTH)]
+    };
+    let values: Vec<i64> = (-2..=SCREEN_WIDTH as i64 + 1).collect();
+
+    // How `X` can change on each cycle boundary: the start of the stretch that ends there and
+    // its value
+    let mut changes: Vec<Option<(usize, i64)>> = vec![None; n_pixels];
+    let mut last = None;
+    for from in start..n_pixels {
+        let candidates = if from == start {
+            vec![x]
+        } else if changes[from].is_some() {
+            values.clone()
+        } else {
+            continue;
+        };
+        for value in candidates {
+            let mut to = from;
+            while to < n_pixels && fits(value, to) {
+                to += 1;
+                // An addx needs two cycles, noops fill out the rest of a longer stretch
+                if to - from >= 2 && to < n_pixels && changes[to].is_none() {
+                    changes[to] = Some((from, value));
+                }
+            }
+            if to == n_pixels && last.is_none() {
+                last = Some((from, value));
+            }
+        }
+    }
+
+    // Walk back from the stretch that reaches the end, then emit in order
+    let mut stretches = vec![last?];
+    while stretches.last().unwrap().0 != start {
+        stretches.push(changes[stretches.last().unwrap().0].unwrap());
+    }
+    stretches.reverse();
+    let mut steps = Vec::new();
+    for (idx, &(from, value)) in stretches.iter().enumerate() {
+        match stretches.get(idx + 1) {
+            Some(&(to, next)) => {
+                steps.extend(std::iter::repeat_n(None, to - from - 2));
+                steps.push(Some(next - value));
+            }
+            None => steps.extend(std::iter::repeat_n(None, n_pixels - from)),
+        }
+    }
+    Some(steps)
+}
+
+/// List a program as source for `assemble`, noting the cycle each instruction starts on and
+/// `X` at the time
+pub fn disassemble(set: &InstructionSet, program: &Program) -> String {
+    let mut listing = String::new();
+    let (mut cycle, mut x) = (1, 1);
+    let labels_at = |idx: usize| {
+        program
+            .labels
+            .iter()
+            .filter(move |(_, at)| *at == idx)
+            .map(|(name, _)| format!("{}:\n", name))
+    };
+    for (idx, instruction) in program.instructions.iter().enumerate() {
+        listing.extend(labels_at(idx));
+        listing.push_str(&format!(
+            "    {:<12} ; cycle {}, X={}\n",
+            set.format(instruction),
+            cycle,
+            x
+        ));
+        let opcode = set.opcode(instruction.opcode);
+        cycle += opcode.cycles;
+        x = (opcode.effect)(x, &instruction.args);
+    }
+    listing.extend(labels_at(program.instructions.len()));
+    listing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cpu::Cpu, draw_screen, render_screen};
+
+    fn assemble_str(source: &str) -> Result<Program, ParseError> {
+        assemble(&InstructionSet::default(), source.as_bytes())
+    }
+
+    fn listing(program: &Program) -> Vec<String> {
+        let set = InstructionSet::default();
+        program
+            .instructions
+            .iter()
+            .map(|instruction| set.format(instruction))
+            .collect()
+    }
+
+    #[test]
+    fn test_assemble() {
+        let program = assemble_str(
+            "; Move the sprite about
+start: noop
+    set 5       ; from 1
+macro pause
+    wait 2
+end
+    pause
+done:
+    until 9
+    addx -1",
+        )
+        .unwrap();
+        assert_eq!(
+            listing(&program),
+            ["noop", "addx 4", "noop", "noop", "noop", "noop", "noop", "addx -1"]
+        );
+        assert_eq!(
+            program.labels,
+            [("start".to_string(), 0), ("done".to_string(), 4)]
+        );
+
+        let set = InstructionSet::default();
+        let mut cpu = Cpu::new(&set, &program.instructions);
+        let xs: Vec<_> = cpu.by_ref().map(|state| state.x).collect();
+        assert_eq!(xs, [1, 1, 1, 5, 5, 5, 5, 5, 5, 5]);
+        assert_eq!(cpu.x(), 4);
+    }
+
+    #[test]
+    fn test_drawing() {
+        let program = assemble_str("text EFZPLBRK").unwrap();
+        let screen = draw_screen(&program.instructions);
+        assert_eq!(glyphs::decode(&screen), "EFZPLBRK");
+
+        let program = assemble_str(
+            "bitmap
+###.
+#..#
+end",
+        )
+        .unwrap();
+        let screen = draw_screen(&program.instructions);
+        let rendered = render_screen(&screen);
+        assert_eq!(&rendered[..4], "###.");
+        assert_eq!(&rendered[41..45], "#..#");
+        assert_eq!(screen.iter().filter(|(_, &lit)| lit).count(), 5);
+    }
+
+    #[test]
+    fn test_listing() {
+        let program = assemble_str("noop\nloop: addx 3\naddx -5\nend:").unwrap();
+        let listing = disassemble(&InstructionSet::default(), &program);
+        assert_eq!(
+            listing,
+            "    noop         ; cycle 1, X=1
+loop:
+    addx 3       ; cycle 2, X=1
+    addx -5      ; cycle 4, X=4
+end:
+"
+        );
+        assert_eq!(assemble_str(&listing).unwrap(), program);
+    }
+
+    #[test]
+    fn test_macro_labels() {
+        let program = assemble_str(
+            "macro blink
+top: set 3
+    set 1
+end
+macro twice
+    blink
+again: blink
+end
+    blink
+    twice",
+        )
+        .unwrap();
+        let labels: Vec<_> = program
+            .labels
+            .iter()
+            .map(|(name, at)| (name.as_str(), *at))
+            .collect();
+        assert_eq!(
+            labels,
+            [("top.1", 0), ("top.3", 2), ("again.2", 4), ("top.4", 4)]
+        );
+        // The listing keeps the numbers and assembles back the same
+        let listing = disassemble(&InstructionSet::default(), &program);
+        assert_eq!(assemble_str(&listing).unwrap(), program);
+        let err = assemble_str("macro m\ntop.1: noop\nend\nm").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "`top.1` is not a valid label")
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = assemble_str("noop\n  jmp 3").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        let err = assemble_str("a: noop\na: noop").unwrap_err();
+        assert_eq!(err.message, "label `a` is already defined");
+        let err = assemble_str("wait 3\nuntil 2").unwrap_err();
+        assert_eq!(err.line, 2);
+        // The sprite starts over the first two pixels, so they are always lit
+        let err = assemble_str("bitmap\n#.\nend").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        let err = assemble_str("bitmap\n#x\nend").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        let err = assemble_str("a.b: noop").unwrap_err();
+        assert_eq!(err.message, "`a.b` is not a valid label");
+        let err = assemble_str("macro loop\nloop\nend\nloop").unwrap_err();
+        assert_eq!(err.message, "macro `loop` expands too deeply");
+        let err = assemble_str("macro pause\nnoop\nend\npause 3").unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (4, 7, "3"));
+        assert_eq!(err.message, "`pause` takes no arguments");
+        let err = assemble_str("noop\na: b: noop").unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (2, 4, "b:"));
+    }
+}
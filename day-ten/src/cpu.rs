@@ -0,0 +1,331 @@
+//! A cycle accurate model of the handheld's CPU.
+//!
+//! Opcodes live in an [`InstructionSet`], each taking a fixed number of cycles and applying its
+//! effect to `X` as its last cycle ends. The [`Cpu`] runs a program one cycle at a time and
+//! reports the state *during* every cycle, either as an iterator or to [`Observer`]s.
+use std::sync::OnceLock;
+
+use aoc_utils::{next_token, parse_token, Grid, ParseError};
+
+use crate::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// What an opcode does to `X` given its arguments
+pub type Effect = fn(x: i64, args: &[i64]) -> i64;
+
+/// An opcode and its cost
+#[derive(Clone, Debug)]
+pub struct Opcode {
+    pub name: String,
+    pub n_args: usize,
+    /// Cycles the instruction takes, at least one
+    pub cycles: usize,
+    pub effect: Effect,
+}
+
+/// The opcodes a program may use
+#[derive(Clone, Debug)]
+pub struct InstructionSet {
+    opcodes: Vec<Opcode>,
+}
+impl Default for InstructionSet {
+    /// `noop` and `addx` from the puzzle
+    fn default() -> Self {
+        let mut set = Self::empty();
+        set.register("noop", 0, 1, |x, _| x);
+        set.register("addx", 1, 2, |x, args| x + args[0]);
+        set
+    }
+}
+impl InstructionSet {
+    /// The puzzle's own set, built the first time it is needed
+    pub fn puzzle() -> &'static Self {
+        static PUZZLE: OnceLock<InstructionSet> = OnceLock::new();
+        PUZZLE.get_or_init(Self::default)
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            opcodes: Vec::new(),
+        }
+    }
+
+    /// Add an opcode, or replace the one with the same name, and return its index
+    ///
+    /// Panics if `cycles` is zero.
+    pub fn register(&mut self, name: &str, n_args: usize, cycles: usize, effect: Effect) -> usize {
+        assert!(cycles > 0, "`{}` must take at least one cycle", name);
+        let opcode = Opcode {
+            name: name.to_string(),
+            n_args,
+            cycles,
+            effect,
+        };
+        match self.lookup(name) {
+            Some(idx) => {
+                self.opcodes[idx] = opcode;
+                idx
+            }
+            None => {
+                self.opcodes.push(opcode);
+                self.opcodes.len() - 1
+            }
+        }
+    }
+
+    /// Index of the opcode called `name`
+    pub fn lookup(&self, name: &str) -> Option<usize> {
+        self.opcodes.iter().position(|opcode| opcode.name == name)
+    }
+
+    /// Panics if `idx` wasn't returned by `register` or `lookup` on this set
+    pub fn opcode(&self, idx: usize) -> &Opcode {
+        &self.opcodes[idx]
+    }
+
+    /// Parse a line like `addx -3`
+    pub fn parse_line(&self, input: &str) -> Result<Instruction, ParseError> {
+        let mut words = input.split_whitespace();
+        let name = next_token(input, &mut words, "instruction")?;
+        let opcode = self.lookup(name).ok_or_else(|| {
+            ParseError::at_token(input, name, format!("unknown instruction `{}`", name))
+        })?;
+        let n_args = self.opcodes[opcode].n_args;
+        let args = (0..n_args)
+            .map(|_| parse_token(input, next_token(input, &mut words, "argument")?))
+            .collect::<Result<_, _>>()?;
+        if let Some(extra) = words.next() {
+            return Err(ParseError::at_token(
+                input,
+                extra,
+                format!("`{}` takes {} arguments", name, n_args),
+            ));
+        }
+        Ok(Instruction { opcode, args })
+    }
+
+    /// Write an instruction back out the way `parse_line` reads it
+    pub fn format(&self, instruction: &Instruction) -> String {
+        let mut line = self.opcode(instruction.opcode).name.clone();
+        for arg in &instruction.args {
+            line.push_str(&format!(" {}", arg));
+        }
+        line
+    }
+}
+
+/// An opcode from an [`InstructionSet`] and its arguments
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub opcode: usize,
+    pub args: Vec<i64>,
+}
+
+/// The CPU during one cycle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CycleState {
+    /// Cycle number, counting from 1
+    pub cycle: usize,
+    /// Value of `X` during the cycle
+    pub x: i64,
+    /// Index of the instruction being executed
+    pub pc: usize,
+}
+
+/// Runs a program one cycle at a time
+pub struct Cpu<'a> {
+    set: &'a InstructionSet,
+    program: &'a [Instruction],
+    x: i64,
+    /// Cycles completed so far
+    cycle: usize,
+    pc: usize,
+    /// Cycles already spent on the current instruction
+    elapsed: usize,
+}
+impl<'a> Cpu<'a> {
+    /// A CPU about to run the first cycle of `program`, with `X` at 1
+    pub fn new(set: &'a InstructionSet, program: &'a [Instruction]) -> Self {
+        Self {
+            set,
+            program,
+            x: 1,
+            cycle: 0,
+            pc: 0,
+            elapsed: 0,
+        }
+    }
+
+    pub fn x(&self) -> i64 {
+        self.x
+    }
+
+    /// Number of cycles completed
+    pub fn cycle_count(&self) -> usize {
+        self.cycle
+    }
+
+    /// Index of the next instruction to finish, or the program's length once it has finished
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// Cycles already spent on the instruction at `pc`
+    pub fn elapsed(&self) -> usize {
+        self.elapsed
+    }
+
+    pub fn instruction_set(&self) -> &'a InstructionSet {
+        self.set
+    }
+
+    pub fn program(&self) -> &'a [Instruction] {
+        self.program
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    /// Run one cycle and return the state during it, or `None` if the program has finished
+    pub fn step(&mut self) -> Option<CycleState> {
+        let instruction = self.program.get(self.pc)?;
+        let state = CycleState {
+            cycle: self.cycle + 1,
+            x: self.x,
+            pc: self.pc,
+        };
+        self.cycle += 1;
+        self.elapsed += 1;
+        let opcode = self.set.opcode(instruction.opcode);
+        if self.elapsed == opcode.cycles {
+            self.x = (opcode.effect)(self.x, &instruction.args);
+            self.pc += 1;
+            self.elapsed = 0;
+        }
+        Some(state)
+    }
+
+    /// Run to the end of the program, showing every cycle to each observer
+    pub fn run(&mut self, observers: &mut [&mut dyn Observer]) {
+        while let Some(state) = self.step() {
+            for observer in observers.iter_mut() {
+                observer.observe(&state);
+            }
+        }
+    }
+}
+impl<'a> Iterator for Cpu<'a> {
+    type Item = CycleState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.step()
+    }
+}
+
+/// Something watching the CPU cycle by cycle
+pub trait Observer {
+    fn observe(&mut self, state: &CycleState);
+}
+impl<F: FnMut(&CycleState)> Observer for F {
+    fn observe(&mut self, state: &CycleState) {
+        self(state)
+    }
+}
+
+/// Sums the signal strength, the cycle number times `X`, over chosen cycles
+#[derive(Clone, Debug, Default)]
+pub struct SignalStrength {
+    cycles: Vec<usize>,
+    pub sum: i64,
+}
+impl SignalStrength {
+    pub fn new(cycles: Vec<usize>) -> Self {
+        Self { cycles, sum: 0 }
+    }
+
+    /// The 20th cycle and every 40 after that up to the 220th, as in the puzzle
+    pub fn puzzle() -> Self {
+        Self::new((20..=220).step_by(40).collect())
+    }
+}
+impl Observer for SignalStrength {
+    fn observe(&mut self, state: &CycleState) {
+        if self.cycles.contains(&state.cycle) {
+            self.sum += state.cycle as i64 * state.x;
+        }
+    }
+}
+
+/// Draws one pixel per cycle, lit if the three pixel wide sprite centred on `X` covers it
+#[derive(Clone, Debug)]
+pub struct Crt {
+    pub screen: Grid<bool>,
+}
+impl Default for Crt {
+    fn default() -> Self {
+        Self {
+            screen: Grid::filled(SCREEN_HEIGHT, SCREEN_WIDTH, false),
+        }
+    }
+}
+impl Crt {
+    /// Pixel drawn during `cycle`, if it is on the screen
+    pub fn pixel(cycle: usize) -> Option<(usize, usize)> {
+        let idx = cycle.checked_sub(1)?;
+        (idx < SCREEN_WIDTH * SCREEN_HEIGHT).then_some((idx / SCREEN_WIDTH, idx % SCREEN_WIDTH))
+    }
+
+    /// Whether the sprite at `x` covers column `col`
+    pub fn is_lit(x: i64, col: usize) -> bool {
+        (x - col as i64).abs() < 2
+    }
+}
+impl Observer for Crt {
+    fn observe(&mut self, state: &CycleState) {
+        if let Some(pixel) = Self::pixel(state.cycle) {
+            self.screen[pixel] = Self::is_lit(state.x, pixel.1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycles() {
+        let set = InstructionSet::default();
+        let program = ["noop", "addx 3", "addx -5"]
+            .iter()
+            .map(|line| set.parse_line(line).unwrap())
+            .collect::<Vec<_>>();
+        let mut cpu = Cpu::new(&set, &program);
+        let xs: Vec<_> = cpu.by_ref().map(|state| (state.x, state.pc)).collect();
+        assert_eq!(xs, [(1, 0), (1, 1), (1, 1), (4, 2), (4, 2)]);
+        assert_eq!((cpu.cycle_count(), cpu.x()), (5, -1));
+    }
+
+    #[test]
+    fn test_registered_opcode() {
+        let mut set = InstructionSet::default();
+        let mulx = set.register("mulx", 1, 3, |x, args| x * args[0]);
+        let program = [
+            set.parse_line("addx 2").unwrap(),
+            set.parse_line("mulx 5").unwrap(),
+        ];
+        assert_eq!(program[1].opcode, mulx);
+        assert_eq!(set.format(&program[1]), "mulx 5");
+
+        let mut xs = Vec::new();
+        let mut signal = SignalStrength::new(vec![3, 6]);
+        let mut record = |state: &CycleState| xs.push(state.x);
+        Cpu::new(&set, &program).run(&mut [&mut signal, &mut record]);
+        assert_eq!(xs, [1, 1, 3, 3, 3]);
+        assert_eq!(signal.sum, 3 * 3);
+
+        let err = set.parse_line("mulx").unwrap_err();
+        assert_eq!(err.message, "expected argument");
+        let err = set.parse_line("noop 1").unwrap_err();
+        assert_eq!(err.column, 6);
+    }
+}
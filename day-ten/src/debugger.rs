@@ -0,0 +1,365 @@
+//! Stepping through a program cycle by cycle, with breakpoints and a trace of every cycle.
+use std::fmt::{self, Display};
+
+use aoc_utils::{next_token, parse_token, ParseError};
+
+use crate::{
+    cpu::{Cpu, Crt, CycleState, Instruction, InstructionSet, Observer},
+    SCREEN_WIDTH,
+};
+
+/// Where to stop the program
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Before the cycle with this number runs
+    Cycle(usize),
+    /// Before the instruction at this index starts
+    Instruction(usize),
+}
+impl Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle(cycle) => write!(f, "cycle {}", cycle),
+            Self::Instruction(idx) => write!(f, "instruction {}", idx),
+        }
+    }
+}
+
+/// Why running stopped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    Breakpoint(Breakpoint),
+    Finished,
+}
+
+/// A command typed at the debugger prompt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugCommand {
+    /// Run this many cycles
+    Step(usize),
+    /// Run until the next instruction starts
+    Next,
+    /// Run until a breakpoint or the end of the program
+    Continue,
+    Break(Breakpoint),
+    /// Remove every breakpoint
+    Clear,
+    Print,
+    Help,
+    Quit,
+}
+impl DebugCommand {
+    pub const HELP: &'static str = "\
+step [n]             run n cycles, 1 by default (s)
+next                 run until the next instruction starts (n)
+continue             run until a breakpoint or the end (c)
+break cycle <n>      stop before cycle n runs (b)
+break instr <i>      stop before instruction i starts (b)
+clear                remove every breakpoint
+print                show the CPU and the CRT row being drawn (p)
+help                 show this message (h)
+quit                 leave the debugger (q)";
+
+    pub fn parse(line: &str) -> Result<Self, ParseError> {
+        let mut words = line.split_whitespace();
+        let command = match next_token(line, &mut words, "a command")? {
+            "s" | "step" => Self::Step(match words.next() {
+                Some(count) => parse_token(line, count)?,
+                None => 1,
+            }),
+            "n" | "next" => Self::Next,
+            "c" | "continue" => Self::Continue,
+            "b" | "break" => {
+                let kind = next_token(line, &mut words, "`cycle` or `instr`")?;
+                let value = parse_token(line, next_token(line, &mut words, "a number")?)?;
+                Self::Break(match kind {
+                    "cycle" => Breakpoint::Cycle(value),
+                    "instr" => Breakpoint::Instruction(value),
+                    other => {
+                        return Err(ParseError::at_token(
+                            line,
+                            other,
+                            format!("expected `cycle` or `instr` but found `{}`", other),
+                        ))
+                    }
+                })
+            }
+            "clear" => Self::Clear,
+            "p" | "print" => Self::Print,
+            "h" | "help" => Self::Help,
+            "q" | "quit" => Self::Quit,
+            other => {
+                return Err(ParseError::at_token(
+                    line,
+                    other,
+                    format!("unknown command `{}`, try `help`", other),
+                ))
+            }
+        };
+        match words.next() {
+            None => Ok(command),
+            Some(extra) => Err(ParseError::at_token(
+                line,
+                extra,
+                format!("unexpected `{}`", extra),
+            )),
+        }
+    }
+}
+
+/// A CPU driving a CRT, which can be stopped at breakpoints
+pub struct Debugger<'a> {
+    cpu: Cpu<'a>,
+    crt: Crt,
+    breakpoints: Vec<Breakpoint>,
+    /// Whether `resume` has been called, as only the first call can stop before any cycle
+    resumed: bool,
+}
+impl<'a> Debugger<'a> {
+    pub fn new(set: &'a InstructionSet, program: &'a [Instruction]) -> Self {
+        Self {
+            cpu: Cpu::new(set, program),
+            crt: Crt::default(),
+            breakpoints: Vec::new(),
+            resumed: false,
+        }
+    }
+
+    pub fn cpu(&self) -> &Cpu<'a> {
+        &self.cpu
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Run one cycle and return the state during it, or `None` if the program has finished
+    pub fn step(&mut self) -> Option<CycleState> {
+        let state = self.cpu.step()?;
+        self.crt.observe(&state);
+        Some(state)
+    }
+
+    /// The breakpoint the CPU is stopped at, if any
+    pub fn breakpoint(&self) -> Option<Breakpoint> {
+        let next_cycle = self.cpu.cycle_count() + 1;
+        let starting = (self.cpu.elapsed() == 0).then_some(self.cpu.pc());
+        self.breakpoints
+            .iter()
+            .copied()
+            .find(|breakpoint| match breakpoint {
+                Breakpoint::Cycle(cycle) => *cycle == next_cycle,
+                Breakpoint::Instruction(idx) => starting == Some(*idx),
+            })
+    }
+
+    /// Run until a breakpoint or the end of the program
+    ///
+    /// At least one cycle runs before breakpoints are checked, so resuming always gets past
+    /// wherever the CPU is stopped. The exception is resuming at the start of the program, so
+    /// that a breakpoint there stops before anything runs.
+    pub fn resume(&mut self) -> Stop {
+        let at_start = !self.resumed && self.cpu.cycle_count() == 0;
+        self.resumed = true;
+        if at_start {
+            if let Some(breakpoint) = self.breakpoint() {
+                return Stop::Breakpoint(breakpoint);
+            }
+        }
+        loop {
+            if self.step().is_none() {
+                return Stop::Finished;
+            }
+            if let Some(breakpoint) = self.breakpoint() {
+                return Stop::Breakpoint(breakpoint);
+            }
+        }
+    }
+
+    /// Run until the CPU is about to start an instruction
+    pub fn next(&mut self) {
+        while self.step().is_some() && self.cpu.elapsed() != 0 {}
+    }
+
+    /// The instruction at `pc` written out, or `None` past the end of the program
+    pub fn instruction(&self, pc: usize) -> Option<String> {
+        let instruction = self.cpu.program().get(pc)?;
+        Some(self.cpu.instruction_set().format(instruction))
+    }
+
+    /// The pixels drawn so far on the row the CRT is drawing
+    pub fn crt_row(&self) -> String {
+        let drawn = self.cpu.cycle_count();
+        if drawn == 0 {
+            return String::new();
+        }
+        let row = (drawn - 1) / SCREEN_WIDTH;
+        if row >= self.crt.screen.n_rows() {
+            return String::new();
+        }
+        let n_cols = (drawn - 1) % SCREEN_WIDTH + 1;
+        self.crt
+            .screen
+            .row(row)
+            .take(n_cols)
+            .map(|&lit| if lit { '#' } else { '.' })
+            .collect()
+    }
+
+    /// The CPU, the instruction it is on and the CRT row being drawn
+    pub fn status(&self) -> String {
+        let cpu = &self.cpu;
+        let instruction = match self.instruction(cpu.pc()) {
+            Some(text) => {
+                let cycles = cpu
+                    .instruction_set()
+                    .opcode(cpu.program()[cpu.pc()].opcode)
+                    .cycles;
+                format!(
+                    "{:>4}: {} ({} of {} cycles done)",
+                    cpu.pc(),
+                    text,
+                    cpu.elapsed(),
+                    cycles
+                )
+            }
+            None => "finished".to_string(),
+        };
+        format!(
+            "after cycle {}, X={}\n{}\nCRT: {}",
+            cpu.cycle_count(),
+            cpu.x(),
+            instruction,
+            self.crt_row()
+        )
+    }
+
+    /// One line about a cycle that has just run
+    pub fn trace_line(&self, state: &CycleState) -> String {
+        let pixel = match Crt::pixel(state.cycle) {
+            Some((row, col)) => format!(
+                "({},{}) {}",
+                row,
+                col,
+                if Crt::is_lit(state.x, col) { '#' } else { '.' }
+            ),
+            None => "off screen".to_string(),
+        };
+        format!(
+            "cycle {:>4}  X={:>4}  {:>4}: {:<12}  {}",
+            state.cycle,
+            state.x,
+            state.pc,
+            self.instruction(state.pc).unwrap_or_default(),
+            pixel
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(set: &InstructionSet) -> Vec<Instruction> {
+        ["noop", "addx 3", "addx -5", "noop"]
+            .iter()
+            .map(|line| set.parse_line(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let set = InstructionSet::default();
+        let program = program(&set);
+        let mut debugger = Debugger::new(&set, &program);
+        debugger.add_breakpoint(Breakpoint::Cycle(3));
+        debugger.add_breakpoint(Breakpoint::Instruction(2));
+
+        // Cycle 3 is the second cycle of `addx 3`
+        assert_eq!(debugger.resume(), Stop::Breakpoint(Breakpoint::Cycle(3)));
+        assert_eq!((debugger.cpu().cycle_count(), debugger.cpu().x()), (2, 1));
+        assert_eq!(
+            debugger.resume(),
+            Stop::Breakpoint(Breakpoint::Instruction(2))
+        );
+        assert_eq!(
+            debugger.status(),
+            "after cycle 3, X=4\n   2: addx -5 (0 of 2 cycles done)\nCRT: ###"
+        );
+        debugger.next();
+        assert_eq!(debugger.cpu().pc(), 3);
+        assert_eq!(debugger.resume(), Stop::Finished);
+        assert!(debugger.status().contains("finished"));
+    }
+
+    #[test]
+    fn test_first_cycle() {
+        let set = InstructionSet::default();
+        let program = program(&set);
+        let mut debugger = Debugger::new(&set, &program);
+        debugger.add_breakpoint(Breakpoint::Cycle(1));
+        debugger.add_breakpoint(Breakpoint::Instruction(0));
+        // Both are before anything has run
+        let stop = debugger.resume();
+        assert_eq!(stop, Stop::Breakpoint(Breakpoint::Cycle(1)));
+        assert_eq!(debugger.cpu().cycle_count(), 0);
+        assert_eq!(debugger.resume(), Stop::Finished);
+
+        let mut debugger = Debugger::new(&set, &program);
+        debugger.add_breakpoint(Breakpoint::Instruction(0));
+        assert_eq!(
+            debugger.resume(),
+            Stop::Breakpoint(Breakpoint::Instruction(0))
+        );
+    }
+
+    #[test]
+    fn test_resume_after_stepping() {
+        let set = InstructionSet::default();
+        let program = program(&set);
+        let mut debugger = Debugger::new(&set, &program);
+        debugger.add_breakpoint(Breakpoint::Instruction(2));
+        // Stepping onto a breakpoint doesn't stop the next resume from getting past it
+        debugger.next();
+        debugger.next();
+        assert_eq!(debugger.breakpoint(), Some(Breakpoint::Instruction(2)));
+        assert_eq!(debugger.resume(), Stop::Finished);
+        assert_eq!(debugger.cpu().cycle_count(), 6);
+    }
+
+    #[test]
+    fn test_trace() {
+        let set = InstructionSet::default();
+        let program = program(&set);
+        let mut debugger = Debugger::new(&set, &program);
+        let mut lines = Vec::new();
+        while let Some(state) = debugger.step() {
+            lines.push(debugger.trace_line(&state));
+        }
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[3], "cycle    4  X=   4     2: addx -5       (0,3) #");
+        assert_eq!(lines[5], "cycle    6  X=  -1     3: noop          (0,5) .");
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(DebugCommand::parse("s").unwrap(), DebugCommand::Step(1));
+        assert_eq!(
+            DebugCommand::parse("step 10").unwrap(),
+            DebugCommand::Step(10)
+        );
+        assert_eq!(
+            DebugCommand::parse("b instr 4").unwrap(),
+            DebugCommand::Break(Breakpoint::Instruction(4))
+        );
+        let err = DebugCommand::parse("break line 4").unwrap_err();
+        assert_eq!(err.column, 7);
+        assert!(DebugCommand::parse("continue now").is_err());
+    }
+}
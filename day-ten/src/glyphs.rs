@@ -0,0 +1,85 @@
+//! Recognition of the capital letters the CRT draws.
+//!
+//! Each letter is 4 pixels wide and 6 tall, with one blank column between letters, so a 40
+//! pixel wide screen holds 8 of them.
+use aoc_utils::Grid;
+
+/// Width of a single letter in pixels
+pub const GLYPH_WIDTH: usize = 4;
+/// Height of a single letter in pixels
+pub const GLYPH_HEIGHT: usize = 6;
+/// Horizontal distance from the start of one letter to the next
+pub const GLYPH_PITCH: usize = GLYPH_WIDTH + 1;
+
+/// Known letters, each row of the glyph concatenated top to bottom
+const GLYPHS: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+/// Letter drawn in the cell starting at column `col`, if it is one we know
+pub fn recognise(screen: &Grid<bool>, col: usize) -> Option<char> {
+    let mut pattern = String::with_capacity(GLYPH_WIDTH * GLYPH_HEIGHT);
+    for row in 0..GLYPH_HEIGHT {
+        for offset in 0..GLYPH_WIDTH {
+            let lit = screen.get((row, col + offset)).copied().unwrap_or(false);
+            pattern.push(if lit { '#' } else { '.' });
+        }
+    }
+    GLYPHS
+        .iter()
+        .find(|(_, glyph)| *glyph == pattern)
+        .map(|(letter, _)| *letter)
+}
+
+/// Read every letter on the screen, using `?` for cells that don't match a known letter
+pub fn decode(screen: &Grid<bool>) -> String {
+    (0..screen.n_cols())
+        .step_by(GLYPH_PITCH)
+        .map(|col| recognise(screen, col).unwrap_or('?'))
+        .collect()
+}
+
+/// Draw `text` on a screen of the given width, the inverse of `decode`
+pub fn draw(text: &str, width: usize) -> Grid<bool> {
+    let mut screen = Grid::filled(GLYPH_HEIGHT, width, false);
+    for (idx, letter) in text.chars().enumerate() {
+        let Some((_, glyph)) = GLYPHS.iter().find(|(known, _)| *known == letter) else {
+            continue;
+        };
+        for (pixel, c) in glyph.chars().enumerate() {
+            let pos = (pixel / GLYPH_WIDTH, idx * GLYPH_PITCH + pixel % GLYPH_WIDTH);
+            if let Some(lit) = screen.get_mut(pos) {
+                *lit = c == '#';
+            }
+        }
+    }
+    screen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let text = "ABCEFGHIJKLOPRSUZ";
+        let screen = draw(text, text.len() * GLYPH_PITCH);
+        assert_eq!(decode(&screen), text);
+    }
+}
@@ -0,0 +1,244 @@
+//! Solutions for part one and part two
+use std::io::BufRead;
+
+use aoc_utils::{try_parse_lines, Grid, ParseError, Solution};
+
+pub mod asm;
+pub mod cpu;
+pub mod debugger;
+pub mod glyphs;
+use cpu::{Cpu, Crt, Instruction, InstructionSet, SignalStrength};
+
+type ReturnType = i64;
+type VectorType = Instruction;
+
+/// Width of the CRT in pixels
+pub const SCREEN_WIDTH: usize = 40;
+/// Height of the CRT in pixels
+pub const SCREEN_HEIGHT: usize = 6;
+
+/// Solution for day ten
+pub struct DayTen;
+impl Solution for DayTen {
+    type Input = Vec<VectorType>;
+    type PartOne = ReturnType;
+    type PartTwo = String;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        try_parse_lines(reader, map_one)
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        Ok(part_one_internal(input))
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        Ok(part_two_internal(input))
+    }
+}
+
+/// Map a line to a VectorType
+fn map_one(input: &str) -> Result<VectorType, ParseError> {
+    InstructionSet::puzzle().parse_line(input)
+}
+
+fn part_one_internal(input: Vec<VectorType>) -> ReturnType {
+    let mut signal = SignalStrength::puzzle();
+    Cpu::new(InstructionSet::puzzle(), &input).run(&mut [&mut signal]);
+    signal.sum
+}
+
+/// Run the program and return which pixels of the CRT it lit
+pub fn draw_screen(input: &[VectorType]) -> Grid<bool> {
+    let mut crt = Crt::default();
+    Cpu::new(InstructionSet::puzzle(), input).run(&mut [&mut crt]);
+    crt.screen
+}
+
+/// Render the screen with `#` for lit pixels and `.` for dark ones
+pub fn render_screen(screen: &Grid<bool>) -> String {
+    screen.map(|&lit| if lit { '#' } else { '.' }).to_string()
+}
+
+fn part_two_internal(input: Vec<VectorType>) -> String {
+    glyphs::decode(&draw_screen(&input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input<'a>() -> &'a str {
+        "addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop"
+    }
+
+    #[test]
+    fn test_one() {
+        let input = try_parse_lines(input().as_bytes(), map_one).unwrap();
+        let output = part_one_internal(input);
+        assert_eq!(output, 13140);
+    }
+
+    #[test]
+    fn test_two() {
+        let input = try_parse_lines(input().as_bytes(), map_one).unwrap();
+        let screen = draw_screen(&input);
+        assert_eq!(
+            render_screen(&screen),
+            "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+"
+        );
+        // The example draws stripes rather than letters
+        assert_eq!(glyphs::decode(&screen), "????????");
+    }
+}
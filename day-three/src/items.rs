@@ -0,0 +1,156 @@
+//! Sets of rucksack items, held as one bit per item.
+//!
+//! There are only 52 kinds of item, `a` to `z` then `A` to `Z`, so a set fits in a `u64` with
+//! bit `p - 1` standing for the item of priority `p`, and intersecting any number of rucksacks
+//! is a run of bitwise ands.
+use std::{
+    fmt::{self, Display},
+    ops::{BitAnd, BitOr},
+    str::FromStr,
+};
+
+use aoc_utils::ParseError;
+
+/// Number of different items
+pub const N_ITEMS: u32 = 52;
+
+/// A set of items
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ItemSet(u64);
+impl ItemSet {
+    pub const EMPTY: Self = Self(0);
+    pub const ALL: Self = Self((1 << N_ITEMS) - 1);
+
+    /// Priority of an item, from 1 for `a` to 52 for `Z`, or `None` if it isn't an item
+    pub fn get_priority(item: char) -> Option<u32> {
+        match item {
+            'a'..='z' => Some(item as u32 - 'a' as u32 + 1),
+            'A'..='Z' => Some(item as u32 - 'A' as u32 + 27),
+            _ => None,
+        }
+    }
+
+    /// The item with a priority, or `None` if no item has it
+    pub fn item(priority: u32) -> Option<char> {
+        match priority {
+            1..=26 => char::from_u32('a' as u32 + priority - 1),
+            27..=N_ITEMS => char::from_u32('A' as u32 + priority - 27),
+            _ => None,
+        }
+    }
+
+    /// The set holding just `item`, or `None` if it isn't an item
+    pub fn single(item: char) -> Option<Self> {
+        Self::get_priority(item).map(|priority| Self(1 << (priority - 1)))
+    }
+
+    /// Items in every one of `sets`, which is no items if there are no sets
+    pub fn intersect_all<I: IntoIterator<Item = Self>>(sets: I) -> Self {
+        sets.into_iter().reduce(BitAnd::bitand).unwrap_or_default()
+    }
+
+    pub fn contains(self, item: char) -> bool {
+        Self::single(item).is_some_and(|single| self & single == single)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Priorities of the items in the set, lowest first
+    pub fn priorities(self) -> impl Iterator<Item = u32> {
+        (1..=N_ITEMS).filter(move |priority| self.0 & (1 << (priority - 1)) != 0)
+    }
+
+    /// The items in the set, in order of priority
+    pub fn items(self) -> impl Iterator<Item = char> {
+        self.priorities().filter_map(Self::item)
+    }
+
+    /// Sum of the priorities of the items in the set
+    pub fn priority_sum(self) -> u32 {
+        self.priorities().sum()
+    }
+}
+impl BitAnd for ItemSet {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+impl BitOr for ItemSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+impl FromStr for ItemSet {
+    type Err = ParseError;
+
+    /// Every character must be an ASCII letter
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .enumerate()
+            .try_fold(Self::EMPTY, |set, (idx, item)| match Self::single(item) {
+                Some(single) => Ok(set | single),
+                None => Err(not_an_item(s, idx + 1, item)),
+            })
+    }
+}
+impl Display for ItemSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.items().try_for_each(|item| write!(f, "{}", item))
+    }
+}
+
+/// Error for a character at a 1-based column that isn't an item
+pub(crate) fn not_an_item(line: &str, column: usize, item: char) -> ParseError {
+    ParseError::at_column(
+        line,
+        column,
+        &item.to_string(),
+        format!(
+            "expected an item from `a` to `z` or `A` to `Z` but found `{}`",
+            item
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priorities() {
+        assert_eq!(ItemSet::get_priority('p'), Some(16));
+        assert_eq!(ItemSet::get_priority('L'), Some(38));
+        assert_eq!(ItemSet::get_priority('1'), None);
+        for priority in 1..=N_ITEMS {
+            let item = ItemSet::item(priority).unwrap();
+            assert_eq!(ItemSet::get_priority(item), Some(priority));
+        }
+        assert_eq!(ItemSet::ALL.len(), 52);
+        assert_eq!(ItemSet::ALL.priority_sum(), 52 * 53 / 2);
+    }
+
+    #[test]
+    fn test_sets() {
+        let sets: Vec<ItemSet> = ["abcZ", "bcZx", "Zcq"]
+            .iter()
+            .map(|items| items.parse().unwrap())
+            .collect();
+        let shared = ItemSet::intersect_all(sets.iter().copied());
+        assert_eq!(shared.to_string(), "cZ");
+        assert!(shared.contains('Z') && !shared.contains('b'));
+        assert_eq!(shared.priority_sum(), 3 + 52);
+        assert!(ItemSet::intersect_all([]).is_empty());
+        let err = "abc-d".parse::<ItemSet>().unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (4, "-"));
+    }
+}
@@ -0,0 +1,202 @@
+//! Solutions for part one and part two
+use std::{
+    fmt::{self, Display},
+    io::BufRead,
+};
+
+use aoc_utils::{try_parse_lines, ParseError, Solution};
+
+pub mod items;
+use items::{not_an_item, ItemSet};
+
+type ReturnType = u64;
+type VectorType = Rucksack;
+
+/// Solution for day three
+pub struct DayThree;
+impl Solution for DayThree {
+    type Input = Vec<VectorType>;
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        try_parse_lines(reader, map_one)
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        Ok(part_one_internal(input))
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        part_two_internal(input)
+    }
+}
+
+// Part1
+// - Parse per line
+// - Cut each line in half and put each half in a set
+// - Find the items that are in both
+// - Count the scores
+
+/// The items in each compartment of a rucksack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rucksack {
+    pub first: ItemSet,
+    pub second: ItemSet,
+}
+impl Rucksack {
+    /// Every character must be an item, and there must be as many in each compartment
+    pub fn new(line: &str) -> Result<Self, ParseError> {
+        if line.is_empty() {
+            return Err(ParseError::missing(line, "the items in a rucksack"));
+        }
+        // Once every character is known to be ASCII, bytes and characters line up
+        let half = line.len() / 2;
+        let mut compartments = [ItemSet::EMPTY; 2];
+        for (idx, item) in line.chars().enumerate() {
+            let single = ItemSet::single(item).ok_or_else(|| not_an_item(line, idx + 1, item))?;
+            let compartment = &mut compartments[usize::from(idx >= half)];
+            *compartment = *compartment | single;
+        }
+        if !line.len().is_multiple_of(2) {
+            return Err(ParseError::at_column(
+                line,
+                line.len(),
+                &line[line.len() - 1..],
+                format!(
+                    "expected an even number of items to split between the compartments but found {}",
+                    line.len()
+                ),
+            ));
+        }
+        let [first, second] = compartments;
+        Ok(Self { first, second })
+    }
+
+    /// Every item in the rucksack
+    pub fn items(&self) -> ItemSet {
+        self.first | self.second
+    }
+
+    /// Items packed in both compartments
+    pub fn misplaced(&self) -> ItemSet {
+        self.first & self.second
+    }
+}
+
+/// Rucksacks that don't split into whole groups
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IncompleteGroup {
+    pub rucksacks: usize,
+    pub group_size: usize,
+}
+impl Display for IncompleteGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rucksacks can't be split into groups of {}",
+            self.rucksacks, self.group_size
+        )
+    }
+}
+
+/// Items carried by every elf in each group of `group_size` rucksacks
+///
+/// Panics if `group_size` is zero.
+pub fn group_badges(
+    rucksacks: &[Rucksack],
+    group_size: usize,
+) -> Result<Vec<ItemSet>, IncompleteGroup> {
+    assert!(group_size > 0, "groups need at least one elf");
+    if !rucksacks.len().is_multiple_of(group_size) {
+        return Err(IncompleteGroup {
+            rucksacks: rucksacks.len(),
+            group_size,
+        });
+    }
+    Ok(rucksacks
+        .chunks(group_size)
+        .map(|group| ItemSet::intersect_all(group.iter().map(Rucksack::items)))
+        .collect())
+}
+
+/// Sum of the priorities of every item in `sets`
+pub fn priority_sum<I: IntoIterator<Item = ItemSet>>(sets: I) -> ReturnType {
+    sets.into_iter()
+        .map(|set| ReturnType::from(set.priority_sum()))
+        .sum()
+}
+
+/// Map a line to a VectorType
+fn map_one(input: &str) -> Result<VectorType, ParseError> {
+    Rucksack::new(input)
+}
+
+fn part_one_internal(input: Vec<VectorType>) -> ReturnType {
+    priority_sum(input.iter().map(Rucksack::misplaced))
+}
+
+fn part_two_internal(input: Vec<VectorType>) -> Result<ReturnType, ParseError> {
+    let badges = group_badges(&input, 3).map_err(|e| ParseError::input(e.to_string()))?;
+    Ok(priority_sum(badges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input<'a>() -> &'a str {
+        "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw"
+    }
+
+    #[test]
+    fn test_one() {
+        let input = DayThree::parse(input().as_bytes()).unwrap();
+        let misplaced: String = input.iter().map(|r| r.misplaced().to_string()).collect();
+        assert_eq!(misplaced, "pLPvts");
+        assert_eq!(part_one_internal(input), 157);
+    }
+
+    #[test]
+    fn test_two() {
+        let input = DayThree::parse(input().as_bytes()).unwrap();
+        let badges = group_badges(&input, 3).unwrap();
+        assert_eq!(badges, ["r".parse().unwrap(), "Z".parse().unwrap()]);
+        assert_eq!(part_two_internal(input.clone()), Ok(70));
+        // Each pair shares something, and all six only what every rucksack has
+        assert_eq!(group_badges(&input, 2).unwrap().len(), 3);
+        assert!(group_badges(&input, 6).unwrap()[0].is_empty());
+        assert_eq!(
+            group_badges(&input, 4),
+            Err(IncompleteGroup {
+                rucksacks: 6,
+                group_size: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        let err = DayThree::parse("abAB\nabcdE\n".as_bytes()).unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (2, 5, "E"));
+        let err = DayThree::parse("ab1B".as_bytes()).unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (1, 3, "1"));
+        let err = DayThree::parse("abé".as_bytes()).unwrap_err();
+        assert_eq!(err.column, 3);
+        let err = DayThree::parse("abAB\n\nabab".as_bytes()).unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "expected the items in a rucksack")
+        );
+        // Part one doesn't mind how many elves there are, but part two needs whole groups
+        let input = DayThree::parse("abAB\nabcd".as_bytes()).unwrap();
+        assert_eq!(part_one_internal(input.clone()), 0);
+        let err = part_two_internal(input).unwrap_err();
+        assert_eq!(err.message, "2 rucksacks can't be split into groups of 3");
+    }
+}
@@ -0,0 +1,285 @@
+//! Solutions for part one and part two
+use std::io::BufRead;
+
+use aoc_utils::{lerp, Direction, Grid, ParseError, Rgb, Solution};
+
+use petgraph::{
+    algo::{astar, dijkstra},
+    graph::{Graph, NodeIndex},
+    visit::{EdgeRef, Reversed},
+};
+
+type ReturnType = usize;
+
+/// Heights of the map, from 1 for `a` to 26 for `z`, with where the climb starts and ends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heightmap {
+    pub heights: Grid<usize>,
+    /// Position of `S`, which is at the height of `a`
+    pub start: (usize, usize),
+    /// Position of `E`, which is at the height of `z`
+    pub end: (usize, usize),
+}
+
+/// Solution for day twelve
+pub struct DayTwelve;
+impl Solution for DayTwelve {
+    type Input = Heightmap;
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        parse_input(reader)
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        part_one_internal(input)
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        part_two_internal(input)
+    }
+}
+
+fn parse_input<R: BufRead>(reader: R) -> Result<Heightmap, ParseError> {
+    let letters = Grid::parse_chars(reader, |c| {
+        (c.is_ascii_lowercase() || c == 'S' || c == 'E').then_some(c)
+    })?;
+    let find = |letter: char| {
+        letters
+            .find(&letter)
+            .ok_or_else(|| ParseError::input(format!("expected `{}` somewhere on the map", letter)))
+    };
+    Ok(Heightmap {
+        start: find('S')?,
+        end: find('E')?,
+        heights: letters.map(|c| convert_letter_to_number(*c)),
+    })
+}
+
+/// Height of a letter on the map, where `S` is at the height of `a` and `E` of `z`
+fn convert_letter_to_number(c: char) -> usize {
+    match c {
+        'S' => convert_letter_to_number('a'),
+        'E' => convert_letter_to_number('z'),
+        _ => c as usize - 'a' as usize + 1,
+    }
+}
+
+// Node is:
+// - value in graph
+// - Index in graph
+//
+// Edge is:
+// - 1 if left, right, up, down and within 1
+// - 99 if not
+
+// We can construct an adjacency matrix
+
+/// Build the climbing graph, returning it alongside the node for each cell
+fn build_graph(input: &Heightmap) -> (Graph<usize, usize>, Grid<NodeIndex>) {
+    let mut g = Graph::<usize, usize>::new();
+    let heights = &input.heights;
+
+    // Create nodes
+    let nodes = heights.map(|height| g.add_node(*height));
+
+    // Create edges to every neighbour we can climb to
+    for (pos, height) in heights.iter() {
+        for neighbour in heights.neighbours4(pos) {
+            if check_nodes(*height as i64, heights[neighbour] as i64) {
+                // Add a path
+                g.update_edge(nodes[pos], nodes[neighbour], 1);
+            }
+        }
+    }
+    (g, nodes)
+}
+
+/// Cell of a node, which `build_graph` adds row by row
+fn node_position(input: &Heightmap, node: NodeIndex) -> (usize, usize) {
+    let n_cols = input.heights.n_cols();
+    (node.index() / n_cols, node.index() % n_cols)
+}
+
+/// Cells along a shortest climb from S to E, both included
+pub fn shortest_path(input: &Heightmap) -> Option<Vec<(usize, usize)>> {
+    let (start_idx, end_idx) = (input.start, input.end);
+
+    let (g, nodes) = build_graph(input);
+    let (_, path) = astar(
+        &g,
+        nodes[start_idx],
+        |n| n == nodes[end_idx],
+        |edge| *edge.weight(),
+        |node| {
+            let (row, col) = node_position(input, node);
+            row.abs_diff(end_idx.0) + col.abs_diff(end_idx.1)
+        },
+    )?;
+    Some(
+        path.into_iter()
+            .map(|node| node_position(input, node))
+            .collect(),
+    )
+}
+
+fn part_one_internal(input: Heightmap) -> Result<ReturnType, ParseError> {
+    shortest_path(&input)
+        .map(|path| path.len() - 1)
+        .ok_or_else(|| ParseError::input("no route climbs from `S` to `E`"))
+}
+
+fn check_nodes(current: i64, next: i64) -> bool {
+    next - current <= 1
+}
+
+/// Fewest steps from any square at the lowest elevation to E, if E can be reached at all
+pub fn fewest_steps_from_lowest(input: &Heightmap) -> Option<usize> {
+    // Walking the climbing edges backwards from the end reaches every start in one search
+    let (g, nodes) = build_graph(input);
+    let distances = dijkstra(Reversed(&g), nodes[input.end], None, |edge| *edge.weight());
+    let lowest = convert_letter_to_number('a');
+    input
+        .heights
+        .iter()
+        // S is at the lowest elevation too
+        .filter(|(_, height)| **height == lowest)
+        .filter_map(|(pos, _)| distances.get(&nodes[pos]).copied())
+        .min()
+}
+
+fn part_two_internal(input: Heightmap) -> Result<ReturnType, ParseError> {
+    fewest_steps_from_lowest(&input)
+        .ok_or_else(|| ParseError::input("no route climbs from the lowest ground to `E`"))
+}
+
+/// Letter for a height, the inverse of `convert_letter_to_number` away from `S` and `E`
+fn height_letter(height: usize) -> char {
+    (b'a' + height as u8 - 1) as char
+}
+
+/// The heightmap with an arrow on each step of `path` pointing to the next one
+pub fn draw_path(input: &Heightmap, path: &[(usize, usize)]) -> Grid<char> {
+    let mut map = input.heights.map(|height| height_letter(*height));
+    map[input.start] = 'S';
+    map[input.end] = 'E';
+    for step in path.windows(2) {
+        let dir = Direction::ALL
+            .into_iter()
+            .find(|dir| input.heights.step(step[0], *dir) == Some(step[1]))
+            .expect("path only moves between neighbours");
+        map[step[0]] = match dir {
+            Direction::North => '^',
+            Direction::South => 'v',
+            Direction::East => '>',
+            Direction::West => '<',
+        };
+    }
+    map
+}
+
+/// Colour of a cell, from green lowlands to white peaks
+pub fn elevation_colour(height: usize) -> Rgb {
+    lerp([30, 90, 40], [245, 245, 245], (height - 1) as f64 / 25.0)
+}
+
+/// Colour used for the route
+const PATH_COLOUR: Rgb = [220, 40, 40];
+
+/// `draw_path` for a terminal, with each cell's background coloured by elevation
+pub fn render_ansi(input: &Heightmap, path: &[(usize, usize)]) -> String {
+    let map = draw_path(input, path);
+    let mut on_path = input.heights.map(|_| false);
+    for pos in path {
+        on_path[*pos] = true;
+    }
+    let mut out = String::new();
+    for row in 0..map.n_rows() {
+        for (col, c) in map.row(row).enumerate() {
+            let [r, g, b] = elevation_colour(input.heights[(row, col)]);
+            out.push_str(&format!("\x1b[48;2;{};{};{}m", r, g, b));
+            if on_path[(row, col)] {
+                let [r, g, b] = PATH_COLOUR;
+                out.push_str(&format!("\x1b[1;38;2;{};{};{}m{}", r, g, b, c));
+            } else {
+                out.push_str(&format!("\x1b[38;2;0;0;0m{}", c));
+            }
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// The heightmap coloured by elevation with the route on top, for `write_ppm`
+pub fn path_image(input: &Heightmap, path: &[(usize, usize)]) -> Grid<Rgb> {
+    let mut image = input.heights.map(|height| elevation_colour(*height));
+    for pos in path {
+        image[*pos] = PATH_COLOUR;
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> &'static str {
+        "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi"
+    }
+
+    #[test]
+    fn test_one() {
+        let input = DayTwelve::parse(input().as_bytes()).unwrap();
+        assert_eq!(part_one_internal(input).unwrap(), 31);
+    }
+
+    #[test]
+    fn test_path() {
+        let input = DayTwelve::parse(input().as_bytes()).unwrap();
+        let path = shortest_path(&input).unwrap();
+        assert_eq!(path.len(), 32);
+        assert_eq!((path[0], path[31]), ((0, 0), (2, 5)));
+        let map = draw_path(&input, &path).to_string();
+        // Every step but the last gets an arrow, and E stays put
+        assert_eq!(map.matches(['>', '<', '^', 'v']).count(), 31);
+        assert_eq!(map.lines().nth(2).unwrap().chars().nth(5), Some('E'));
+        assert!(!map.contains('S'));
+    }
+
+    #[test]
+    fn test_two() {
+        let input = DayTwelve::parse(input().as_bytes()).unwrap();
+        assert_eq!(part_two_internal(input).unwrap(), 29);
+    }
+
+    #[test]
+    fn test_heights() {
+        // S is as low as `a` and E as high as `z`
+        let input = DayTwelve::parse("SbcdefghijklmnopqrstuvwxyE".as_bytes()).unwrap();
+        assert_eq!(part_one_internal(input).unwrap(), 25);
+        // Only the `a` can get to E, which part two still finds
+        let walled = "SzabcdefghijklmnopqrstuvwxyzE\nzzzzzzzzzzzzzzzzzzzzzzzzzzzzz";
+        let input = DayTwelve::parse(walled.as_bytes()).unwrap();
+        assert_eq!(part_two_internal(input.clone()).unwrap(), 26);
+        let err = part_one_internal(input).unwrap_err();
+        assert_eq!(err.message, "no route climbs from `S` to `E`");
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let walled = input().replace("accszExk", "accszEzk").replace("xx", "zz");
+        let map = DayTwelve::parse(walled.as_bytes()).unwrap();
+        assert_eq!(shortest_path(&map), None);
+        assert_eq!(fewest_steps_from_lowest(&map), None);
+        assert!(part_two_internal(map).is_err());
+        let err = DayTwelve::parse(input().replace('E', "z").as_bytes()).unwrap_err();
+        assert_eq!(err.message, "expected `E` somewhere on the map");
+        let err = DayTwelve::parse("Sab\naEc\nab!".as_bytes()).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 3));
+    }
+}
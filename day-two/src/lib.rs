@@ -0,0 +1,190 @@
+//! Solutions for part one and part two
+use std::io::BufRead;
+
+use aoc_utils::{next_token, try_parse_lines, ParseError, Solution};
+
+type ReturnType = u64;
+type VectorType = Game;
+
+/// Solution for day two
+pub struct DayTwo;
+impl Solution for DayTwo {
+    /// Each line read both as a played move and as a desired outcome
+    type Input = Vec<(VectorType, VectorType)>;
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        parse_input(reader)
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        Ok(part_one_internal(
+            input.into_iter().map(|(game, _)| game).collect(),
+        ))
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        Ok(part_two_internal(
+            input.into_iter().map(|(_, game)| game).collect(),
+        ))
+    }
+}
+
+#[derive(Clone)]
+enum Rpc {
+    Rock,
+    Paper,
+    Scissors,
+}
+impl Rpc {
+    fn try_parse(line: &str, input: &str) -> Result<Self, ParseError> {
+        use Rpc::*;
+        match input {
+            "A" => Ok(Rock),
+            "B" => Ok(Paper),
+            "C" => Ok(Scissors),
+            "X" => Ok(Rock),
+            "Y" => Ok(Paper),
+            "Z" => Ok(Scissors),
+            _ => Err(ParseError::at_token(
+                line,
+                input,
+                format!("expected one of A, B, C, X, Y, Z but found `{}`", input),
+            )),
+        }
+    }
+
+    fn get_points(&self) -> ReturnType {
+        match self {
+            Self::Rock => 1,
+            Self::Paper => 2,
+            Self::Scissors => 3,
+        }
+    }
+
+    fn gen_win(&self) -> Self {
+        match self {
+            Self::Rock => Self::Scissors,
+            Self::Paper => Self::Rock,
+            Self::Scissors => Self::Paper,
+        }
+    }
+    fn gen_tie(&self) -> Self {
+        self.clone()
+    }
+    fn gen_loss(&self) -> Self {
+        match self {
+            Self::Rock => Self::Paper,
+            Self::Paper => Self::Scissors,
+            Self::Scissors => Self::Rock,
+        }
+    }
+}
+
+enum Strategy {
+    Win,
+    Lose,
+    Tie,
+}
+impl Strategy {
+    fn try_from_xyz(line: &str, input: &str) -> Result<Self, ParseError> {
+        match input {
+            "X" => Ok(Strategy::Lose),
+            "Y" => Ok(Strategy::Tie),
+            "Z" => Ok(Strategy::Win),
+            _ => Err(ParseError::at_token(
+                line,
+                input,
+                format!("expected one of X, Y, Z but found `{}`", input),
+            )),
+        }
+    }
+}
+
+pub struct Game {
+    you: Rpc,
+    opponent: Rpc,
+}
+impl Game {
+    fn from_line(input: &str) -> Result<Self, ParseError> {
+        let mut inputs = input.split_whitespace();
+        let opponent = Rpc::try_parse(input, next_token(input, &mut inputs, "opponent move")?)?;
+        let you = Rpc::try_parse(input, next_token(input, &mut inputs, "your move")?)?;
+        Ok(Self { you, opponent })
+    }
+
+    fn from_strategy(input: &str) -> Result<Self, ParseError> {
+        let mut inputs = input.split_whitespace();
+
+        let opponent = Rpc::try_parse(input, next_token(input, &mut inputs, "opponent move")?)?;
+        let strategy =
+            Strategy::try_from_xyz(input, next_token(input, &mut inputs, "desired outcome")?)?;
+        let you = match strategy {
+            Strategy::Win => opponent.gen_loss(),
+            Strategy::Tie => opponent.gen_tie(),
+            Strategy::Lose => opponent.gen_win(),
+        };
+        Ok(Self { you, opponent })
+    }
+
+    fn score(&self) -> ReturnType {
+        let opposition_score = match self.you {
+            Rpc::Rock => match self.opponent {
+                Rpc::Rock => 3,
+                Rpc::Paper => 0,
+                Rpc::Scissors => 6,
+            },
+            Rpc::Paper => match self.opponent {
+                Rpc::Rock => 6,
+                Rpc::Paper => 3,
+                Rpc::Scissors => 0,
+            },
+            Rpc::Scissors => match self.opponent {
+                Rpc::Rock => 0,
+                Rpc::Paper => 6,
+                Rpc::Scissors => 3,
+            },
+        };
+        opposition_score + self.you.get_points()
+    }
+}
+
+fn parse_input<R: BufRead>(reader: R) -> Result<Vec<(VectorType, VectorType)>, ParseError> {
+    try_parse_lines(reader, |line| {
+        Ok((Game::from_line(line)?, Game::from_strategy(line)?))
+    })
+}
+
+fn part_one_internal(input: Vec<VectorType>) -> ReturnType {
+    input.iter().map(|x| x.score()).sum()
+}
+
+fn part_two_internal(input: Vec<VectorType>) -> ReturnType {
+    input.iter().map(|x| x.score()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input<'a>() -> &'a str {
+        "A Y
+B X
+C Z"
+    }
+
+    #[test]
+    fn test_one() {
+        let input = DayTwo::parse(input().as_bytes()).unwrap();
+        assert_eq!(DayTwo::part_one(input).unwrap(), 15);
+    }
+
+    #[test]
+    fn test_two() {
+        let input = DayTwo::parse(input().as_bytes()).unwrap();
+        assert_eq!(DayTwo::part_two(input).unwrap(), 12);
+        let err = DayTwo::parse("A Y\nB W".as_bytes()).err().unwrap();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+}
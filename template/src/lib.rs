@@ -0,0 +1,67 @@
+//! Solutions for part one and part two
+use std::io::BufRead;
+
+use aoc_utils::{try_parse_lines, ParseError, Solution};
+
+// TODO -- Update this with the return type
+type ReturnType = u64;
+type VectorType = u32;
+
+/// Solution for {{project-name}}
+pub struct {{project-name | pascal_case}};
+impl Solution for {{project-name | pascal_case}} {
+    type Input = Vec<VectorType>;
+    type PartOne = ReturnType;
+    type PartTwo = ReturnType;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, ParseError> {
+        try_parse_lines(reader, map_one)
+    }
+
+    fn part_one(input: Self::Input) -> Result<Self::PartOne, ParseError> {
+        Ok(part_one_internal(input))
+    }
+
+    fn part_two(input: Self::Input) -> Result<Self::PartTwo, ParseError> {
+        Ok(part_two_internal(input))
+    }
+}
+
+/// Map a line to a VectorType
+fn map_one(input: &str) -> Result<VectorType, ParseError> {
+    todo!()
+}
+
+// TODO Implement this
+fn part_one_internal(input: Vec<VectorType>) -> ReturnType {
+    todo!()
+}
+
+// TODO Implement this
+fn part_two_internal(input: Vec<VectorType>) -> ReturnType {
+    todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // TODO Paste the example from the puzzle
+    fn input<'a>() -> &'a str {
+        ""
+    }
+
+    #[test]
+    fn test_one() {
+        let input = {{project-name | pascal_case}}::parse(input().as_bytes()).unwrap();
+        // TODO Update this with the example's answer
+        assert_eq!(part_one_internal(input), 0);
+    }
+
+    #[test]
+    fn test_two() {
+        let input = {{project-name | pascal_case}}::parse(input().as_bytes()).unwrap();
+        // TODO Update this with the example's answer
+        assert_eq!(part_two_internal(input), 0);
+    }
+}